@@ -5,19 +5,25 @@ use nft_info::{read_nft, remove_nft, Action};
 use soroban_sdk::{Address, Env};
 use storage_types::TokenId;
 use user_info::{read_owner_card, read_user, write_owner_card, write_user};
-use crate::event::emit_burn;
+use crate::curve;
+use crate::error::NFTError;
+use crate::event::{card_event, emit_burn, CardEvent};
 
-pub fn burn(env: Env, user: Address, token_id: TokenId) {
+pub fn burn(env: Env, user: Address, token_id: TokenId) -> Result<(), NFTError> {
     user.require_auth();
     let mut user = read_user(&env, user.clone());
     let owner = user.owner.clone();
 
     let config = read_config(&env);
-    let nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
+    let nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
     let card_metadata = read_metadata(&env, token_id.0);
 
     // Calculate Terry and Power amounts
-    let terry_amount = card_metadata.price_terry * (nft.power as i128 / card_metadata.initial_power as i128) /2;
+    let price_terry = curve::burn_price_terry(&env, token_id.0, card_metadata.price_terry);
+    let terry_amount = price_terry * (nft.power as i128 / card_metadata.initial_power as i128) /2;
     let receive_amount = terry_amount * config.burn_receive_percentage as i128 / 100;
     let pot_terry = terry_amount - receive_amount; // Terry to pot
     let total_power = card_metadata.initial_power + nft.power / 2;
@@ -32,16 +38,31 @@ pub fn burn(env: Env, user: Address, token_id: TokenId) {
     crate::pot::management::accumulate_pot_internal(&env, pot_terry, pot_power as u32, 0, Some(owner.clone()), Some(Action::Burn));
 
     // Emit burn event
-    emit_burn(&env, &owner);
+    emit_burn(
+        &env,
+        &owner,
+        &CardEvent {
+            power: receive_power as u32,
+            terry: receive_amount,
+            card_power: 0,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     // Remove card and NFT
-    remove_owner_card(&env, owner.clone(), token_id.clone());
+    curve::record_burn(&env, token_id.0);
+    remove_owner_card(&env, owner.clone(), token_id.clone())?;
     remove_nft(&env, owner, token_id);
+    Ok(())
 }
 
-pub fn remove_owner_card(env: &Env, owner: Address, token_id: TokenId) {
+pub fn remove_owner_card(env: &Env, owner: Address, token_id: TokenId) -> Result<(), NFTError> {
     let mut user_card_ids = read_owner_card(&env, owner.clone());
-    let index = user_card_ids.iter().position(|x| x == token_id).unwrap();
+    let index = user_card_ids
+        .iter()
+        .position(|x| x == token_id)
+        .ok_or(NFTError::CardNotFound)?;
     user_card_ids.remove(index as u32);
     write_owner_card(&env, owner.clone(), user_card_ids);
+    Ok(())
 }
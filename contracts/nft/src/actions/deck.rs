@@ -2,14 +2,31 @@ use crate::{admin::read_config, user_info::mint_terry, *};
 use admin::{read_balance, write_balance};
 use metadata::read_metadata;
 use nft_info::{read_nft, write_nft, Action};
-use soroban_sdk::{log, vec, Address, Env, Vec};
+use soroban_sdk::{vec, Address, Env, Vec};
 use storage_types::{DataKey, Deck, TokenId, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
 use user_info::read_user;
-use crate::event::{emit_deck_place, emit_deck_replace, emit_deck_remove, emit_deck_completed};
+use crate::error::NFTError;
+use crate::ledger::{post, Account, Asset};
+use crate::governance::weights;
+use crate::pot::distribution::set_effective_power;
+use crate::pot::management::calculate_effective_power;
+use crate::event::{
+    card_event, emit_deck_completed, emit_deck_place, emit_deck_remove, emit_deck_replace,
+    CardEvent,
+};
 
 fn write_deck(env: Env, user: Address, deck: Deck) {
     let owner = read_user(&env, user).owner;
 
+    // Only complete decks earn pot rewards
+    let effective_power = if deck.token_ids.len() == 4 {
+        calculate_effective_power(deck.total_power, deck.bonus)
+    } else {
+        0
+    };
+    set_effective_power(&env, &owner, effective_power);
+    weights::set_deck_weight(&env, &owner, effective_power);
+
     let key = DataKey::Deck(owner.clone());
     env.storage().persistent().set(&key, &deck);
     #[cfg(not(test))]
@@ -98,56 +115,75 @@ pub fn read_deck(env: Env, user: Address) -> Deck {
         .unwrap_or(new_deck.clone())
 }
 
-pub fn place(env: Env, user: Address, token_id: TokenId) {
+pub fn place(env: Env, user: Address, token_id: TokenId) -> Result<(), NFTError> {
     let mut deck = read_deck(env.clone(), user.clone());
 
-    assert!(deck.token_ids.len() < 4, "Decks are exceed!");
+    if deck.token_ids.len() >= 4 {
+        return Err(NFTError::DeckFull);
+    }
 
-    let mut nft = read_nft(&env, user.clone(), token_id.clone()).unwrap();
+    let mut nft = read_nft(&env, user.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
 
-    assert!(
-        nft.locked_by_action == Action::None,
-        "Locked by other action"
-    );
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
 
     nft.locked_by_action = Action::Deck;
+    let card_power = nft.power;
 
     write_nft(&env, user.clone(), token_id.clone(), nft.clone());
     deck.token_ids.push_back(token_id.clone());
 
-    let deck_size = deck.token_ids.len();
-
     if deck.token_ids.len() == 4 {
-        calculate_deck_balance(env.clone(), user.clone(), &mut deck);
+        calculate_deck_balance(env.clone(), user.clone(), &mut deck)?;
     }
     write_deck(env.clone(), user.clone(), deck);
 
+    let config = read_config(&env);
+
     // Emit deck place event
-    emit_deck_place(&env, &user);
+    emit_deck_place(
+        &env,
+        &user,
+        &CardEvent {
+            terry: config.terry_per_deck,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
-    let config = read_config(&env);
     mint_terry(&env, user.clone(), config.terry_per_deck);
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_terry += config.terry_per_deck * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_deck * config.haw_ai_percentage as i128 / 100,
+        Action::Deck,
+    );
+    Ok(())
 }
 
-pub fn replace(env: Env, user: Address, prev_token_id: TokenId, token_id: TokenId) {
+pub fn replace(
+    env: Env,
+    user: Address,
+    prev_token_id: TokenId,
+    token_id: TokenId,
+) -> Result<(), NFTError> {
     let mut deck = read_deck(env.clone(), user.clone());
 
-    let mut prev_nft = read_nft(&env, user.clone(), prev_token_id.clone()).unwrap();
+    let mut prev_nft =
+        read_nft(&env, user.clone(), prev_token_id.clone()).ok_or(NFTError::CardNotFound)?;
 
-    assert!(
-        prev_nft.locked_by_action == Action::Deck,
-        "Not locked by Deck"
-    );
+    if prev_nft.locked_by_action != Action::Deck {
+        return Err(NFTError::CardNotInDeck);
+    }
 
-    let mut nft = read_nft(&env, user.clone(), token_id.clone()).unwrap();
-    assert!(
-        nft.locked_by_action == Action::None,
-        "Locked by other action"
-    );
+    let mut nft = read_nft(&env, user.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
     if let Some(index) = deck
         .token_ids
         .iter()
@@ -161,45 +197,60 @@ pub fn replace(env: Env, user: Address, prev_token_id: TokenId, token_id: TokenI
 
     write_nft(&env, user.clone(), prev_token_id.clone(), prev_nft);
     if deck.token_ids.len() == 4 {
-        calculate_deck_balance(env.clone(), user.clone(), &mut deck);
+        calculate_deck_balance(env.clone(), user.clone(), &mut deck)?;
     }
     write_deck(env.clone(), user.clone(), deck);
 
     // Emit deck replace event
-    emit_deck_replace(&env, &user);
+    emit_deck_replace(
+        &env,
+        &user,
+        prev_token_id.0,
+        &CardEvent {
+            card_power: nft.power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     nft.locked_by_action = Action::Deck;
     write_nft(&env, user.clone(), token_id.clone(), nft);
+    Ok(())
 }
 
-pub fn update_deck(env: Env, user: Address, token_ids: Vec<TokenId>) {
+pub fn update_deck(env: Env, user: Address, token_ids: Vec<TokenId>) -> Result<(), NFTError> {
     let mut deck = read_deck(env.clone(), user.clone());
 
-    assert!(token_ids.len() <= 4, "Decks cannot exceed 4 cards!");
+    if token_ids.len() > 4 {
+        return Err(NFTError::DeckFull);
+    }
 
     deck.token_ids = token_ids;
 
     if deck.token_ids.len() == 4 {
-        calculate_deck_balance(env.clone(), user.clone(), &mut deck);
+        calculate_deck_balance(env.clone(), user.clone(), &mut deck)?;
     }
     write_deck(env.clone(), user.clone(), deck);
+    Ok(())
 }
 
-pub fn remove_place(env: Env, user: Address, token_id: TokenId) {
+pub fn remove_place(env: Env, user: Address, token_id: TokenId) -> Result<(), NFTError> {
     let mut deck = read_deck(env.clone(), user.clone());
 
-    assert!(deck.token_ids.len() > 0, "Decks are null!");
-
-    let mut nft = read_nft(&env, user.clone(), token_id.clone()).unwrap();
+    if deck.token_ids.is_empty() {
+        return Err(NFTError::DeckEmpty);
+    }
 
-    log!(&env, "deck token id length {}", deck.token_ids.len());
+    let mut nft = read_nft(&env, user.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Deck {
+        return Err(NFTError::CardNotInDeck);
+    }
 
     if let Some(index) = deck.token_ids.iter().position(|x| x == token_id.clone()) {
         deck.token_ids.remove(index.try_into().unwrap());
     }
 
-    assert!(nft.locked_by_action == Action::Deck, "Not locked by Deck");
     nft.locked_by_action = Action::None;
+    let card_power = nft.power;
 
     write_nft(&env, user.clone(), token_id.clone(), nft.clone());
 
@@ -214,27 +265,46 @@ pub fn remove_place(env: Env, user: Address, token_id: TokenId) {
     deck.bonus = 0;
     deck.deck_categories = 0;
 
-    let deck_size = deck.token_ids.len();
-
     write_deck(env.clone(), user.clone(), deck);
     update_haw_ai_percentages(env.clone());
 
+    let config = read_config(&env);
+
     // Emit deck remove event
-    emit_deck_remove(&env, &user);
+    emit_deck_remove(
+        &env,
+        &user,
+        &CardEvent {
+            terry: config.terry_per_deck,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
-    let config = read_config(&env);
     mint_terry(&env, user.clone(), config.terry_per_deck);
 
-    balance.haw_ai_terry += config.terry_per_deck * config.haw_ai_percentage as i128 / 100;
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_deck * config.haw_ai_percentage as i128 / 100,
+        Action::Deck,
+    );
     write_balance(&env, &balance);
+    Ok(())
 }
 
-pub fn calculate_deck_balance(env: Env, player_address: Address, deck: &mut Deck) {
+pub fn calculate_deck_balance(
+    env: Env,
+    player_address: Address,
+    deck: &mut Deck,
+) -> Result<(), NFTError> {
     let mut unique_categories = vec![&env.clone()];
     let mut total_power = 0;
     if deck.token_ids.len() == 4 {
         for id in deck.token_ids.iter() {
-            let _nft = read_nft(&env, player_address.clone(), id.clone()).unwrap();
+            let _nft = read_nft(&env, player_address.clone(), id.clone()).ok_or(NFTError::CardNotFound)?;
             let metadata = read_metadata(&env, id.clone().0);
             let category = metadata.category;
 
@@ -262,10 +332,11 @@ pub fn calculate_deck_balance(env: Env, player_address: Address, deck: &mut Deck
         deck.deck_categories = deck_categories;
 
         // Emit deck completed event
-        emit_deck_completed(&env, &player_address);
+        emit_deck_completed(&env, &player_address, total_power, bonus, deck_categories);
 
         update_haw_ai_percentages(env.clone());
     }
+    Ok(())
 }
 // pub fn remove_all_place(env: Env, user: Address) {
 //     let deck = read_deck(env.clone(), user.clone());
@@ -291,6 +362,48 @@ pub fn calculate_deck_balance(env: Env, player_address: Address, deck: &mut Deck
 //     // update haw ai percentage
 //
 
+/// Adds up to `amount` POWER to the player's deck cards in proportion to their
+/// power (equally if they have none), each capped at its metadata `max_power`.
+/// Returns the POWER added.
+pub fn add_power(env: &Env, user: Address, amount: u32) -> u32 {
+    let mut deck = read_deck(env.clone(), user.clone());
+    let owner = deck.owner.clone();
+    let mut cards = Vec::new(env);
+    for id in deck.token_ids.iter() {
+        if let Some(nft) = read_nft(env, owner.clone(), id.clone()) {
+            cards.push_back((id, nft));
+        }
+    }
+    let weights: u64 = cards.iter().map(|(_, nft)| nft.power as u64).sum();
+
+    let mut added = 0;
+    for (i, (id, mut nft)) in cards.iter().enumerate() {
+        let share = (amount as u64 * nft.power as u64)
+            .checked_div(weights)
+            .map_or(amount / cards.len(), |share| share as u32);
+        // The last card takes the rounding remainder
+        let share = if i as u32 == cards.len() - 1 { amount - added } else { share };
+        let room = read_metadata(env, id.0).max_power.saturating_sub(nft.power);
+        let share = share.min(room).min(amount - added);
+        if share == 0 {
+            continue;
+        }
+        nft.power += share;
+        write_nft(env, owner.clone(), id, nft);
+        added += share;
+    }
+
+    if added > 0 && deck.token_ids.len() == 4 {
+        let mut balance = read_balance(env);
+        balance.total_deck_power += added;
+        write_balance(env, &balance);
+        deck.total_power += added;
+        write_deck(env.clone(), owner, deck);
+        update_haw_ai_percentages(env.clone());
+    }
+    added
+}
+
 pub fn update_haw_ai_percentages(env: Env) {
     let decks = read_decks(env.clone());
     let balance = read_balance(&env);
@@ -1,11 +1,20 @@
 use crate::{nft_info::remove_nft, user_info::mint_terry, *};
-use admin::{read_balance, read_config, write_balance};
+use admin::read_config;
 use nft_info::{read_nft, write_nft, Action, Category};
-use soroban_sdk::{contracttype, symbol_short, vec, Address, Env, IntoVal, Symbol, Val, Vec, log};
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Symbol, Val, Vec, log};
 use storage_types::{DataKey, TokenId, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
 use user_info::read_user;
 
 use super::remove_owner_card;
+use crate::error::NFTError;
+use crate::fees::read_action_fees;
+use crate::event::{
+    card_event, emit_fight_close, emit_fight_liquidated, emit_fight_open, emit_fight_removed, emit_fight_stored,
+    CardEvent,
+};
+
+/// Oldest oracle price accepted, in seconds.
+pub const ORACLE_MAX_AGE: u64 = 3600;
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -17,8 +26,8 @@ pub enum Asset {
 //price record definition
 #[contracttype]
 pub struct PriceData {
-    price: i128,    //asset price at given point in time
-    timestamp: u64, //recording timestamp
+    pub price: i128,    //asset price at given point in time
+    pub timestamp: u64, //recording timestamp
 }
 
 #[contracttype]
@@ -77,20 +86,22 @@ pub fn write_fight(env: Env, user: Address, category: Category, token_id: TokenI
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 
-    env.events().publish(
-        (symbol_short!("fight"), symbol_short!("open")),
-        fight.clone(),
-    )
+    emit_fight_stored(&env, &fight);
 }
 
-pub fn read_fight(env: Env, user: Address, category: Category, token_id: TokenId) -> Fight {
+pub fn read_fight(env: Env, user: Address, category: Category, token_id: TokenId) -> Result<Fight, NFTError> {
     let owner = read_user(&env, user).owner;
 
     let key = DataKey::Fight(owner.clone(), category.clone(), token_id.clone());
+    let fight: Fight = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(NFTError::FightNotFound)?;
     env.storage()
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
-    env.storage().persistent().get(&key).unwrap()
+    Ok(fight)
 }
 
 pub fn remove_fight(env: Env, user: Address, category: Category, token_id: TokenId) {
@@ -101,16 +112,14 @@ pub fn remove_fight(env: Env, user: Address, category: Category, token_id: Token
     if let Some(pos) = fights.iter().position(|fight| {
         fight.owner == owner && fight.category == category && fight.token_id == token_id
     }) {
-        let fight = read_fight(
+        if let Ok(fight) = read_fight(
             env.clone(),
             owner.clone(),
             category.clone(),
             token_id.clone(),
-        );
-        env.events().publish(
-            (symbol_short!("fight"), symbol_short!("close")),
-            fight.clone(),
-        );
+        ) {
+            emit_fight_removed(&env, &fight);
+        }
         fights.remove(pos.try_into().unwrap());
     }
 
@@ -134,7 +143,11 @@ pub fn read_fights(env: Env) -> Vec<Fight> {
         .unwrap_or(vec![&env.clone()])
 }
 
-pub fn get_currency_price(env: Env, oracle_contract_id: Address, currency: FightCurrency) -> i128 {
+pub fn get_currency_price(
+    env: Env,
+    oracle_contract_id: Address,
+    currency: FightCurrency,
+) -> Result<i128, NFTError> {
     // let config = read_config(&env);
     let asset = match currency {
         FightCurrency::BTC => Asset::Other(Symbol::new(&env, "BTC")),
@@ -143,17 +156,25 @@ pub fn get_currency_price(env: Env, oracle_contract_id: Address, currency: Fight
         FightCurrency::XLM => Asset::Other(Symbol::new(&env, "XLM")),
         FightCurrency::SOL => Asset::Other(Symbol::new(&env, "SOL")),
     };
-    let args: Vec<Val> = (asset.clone(),).into_val(&env);
-    let function_symbol = Symbol::new(&env, "lastprice");
+    read_oracle_price(&env, &oracle_contract_id, &asset)
+}
+
+/// Latest oracle price for `asset`, rejecting missing, out-of-range and stale prices.
+pub fn read_oracle_price(env: &Env, oracle_contract_id: &Address, asset: &Asset) -> Result<i128, NFTError> {
+    let args: Vec<Val> = (asset.clone(),).into_val(env);
+    let function_symbol = Symbol::new(env, "lastprice");
 
     let asset_price: Option<PriceData> =
-        env.invoke_contract(&oracle_contract_id, &function_symbol, args);
+        env.invoke_contract(oracle_contract_id, &function_symbol, args);
 
-    if let Some(asset_price) = asset_price {
-        asset_price.price
-    } else {
-        0
+    let asset_price = asset_price.ok_or(NFTError::InvalidOraclePrice)?;
+    if asset_price.price <= 0 || asset_price.price >= i128::MAX / 100 {
+        return Err(NFTError::InvalidOraclePrice);
+    }
+    if env.ledger().timestamp().saturating_sub(asset_price.timestamp) > ORACLE_MAX_AGE {
+        return Err(NFTError::StaleOracle);
     }
+    Ok(asset_price.price)
 }
 
 pub fn get_liquidation_price(fight: &Fight) -> i128 {
@@ -173,48 +194,67 @@ pub fn check_liquidation(
     user: Address,
     category: Category,
     token_id: TokenId,
-) {
+) -> Result<(), NFTError> {
     // Require authorization from liquidator (can be anyone, but must be authenticated)
     liquidator.require_auth();
+    if !env
+        .storage()
+        .persistent()
+        .has(&DataKey::Fight(user.clone(), category.clone(), token_id.clone()))
+    {
+        return Err(NFTError::FightNotFound);
+    }
     let fight = read_fight(
         env.clone(),
         user.clone(),
         category.clone(),
         token_id.clone(),
-    );
+    )?;
     let config = read_config(&env);
     let current_price = get_currency_price(
         env.clone(),
         config.oracle_contract_id,
         fight.currency.clone(),
-    );
-    // Validate oracle price is recent and reasonable
-    assert!(current_price > 0, "Invalid oracle price");
-    assert!(current_price < i128::MAX / 2, "Oracle price too high");
+    )?;
     let liq_price = get_liquidation_price(&fight);
     let is_liquidated = match fight.side_position {
         SidePosition::Long => current_price <= liq_price,
         SidePosition::Short => current_price >= liq_price,
     };
     // Only allow liquidation if position is actually underwater
-    assert!(is_liquidated, "Position is not liquidatable");
-    if is_liquidated {
-        let mut nft = read_nft(&env, user.clone(), token_id.clone()).unwrap();
+    if !is_liquidated {
+        return Err(NFTError::NotLiquidatable);
+    }
+    {
+        let mut nft = read_nft(&env, user.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
         // Mint TERRY rewards to user
         let terry_reward = config.terry_per_fight;
         mint_terry(&env, user.clone(), terry_reward);
 
+        emit_fight_liquidated(
+            &env,
+            &user,
+            &liquidator,
+            &CardEvent {
+                power: fight.power,
+                terry: terry_reward,
+                card_power: nft.power,
+                ..card_event(&env, &token_id)
+            },
+        );
+
         // Handle NFT based on final power
         if nft.power > 0 {
             nft.locked_by_action = Action::None;
             write_nft(&env, user.clone(), token_id.clone(), nft);
         } else {
-            remove_owner_card(&env, user.clone(), token_id.clone());
+            remove_owner_card(&env, user.clone(), token_id.clone())?;
             remove_nft(&env, user.clone(), token_id.clone());
         }
         // Remove fight position
         remove_fight(env.clone(), user.clone(), category.clone(), token_id);
     }
+    Ok(())
 }
 
 pub fn open_position(
@@ -226,25 +266,29 @@ pub fn open_position(
     side_position: SidePosition,
     leverage: u32,
     power_staked: u32,
-) {
+) -> Result<(), NFTError> {
     user.require_auth();
     let owner = read_user(&env, user).owner;
-    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
+    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
     log!(&env, "fight >> nft to fight = ", nft);
-    assert_eq!(nft.locked_by_action, Action::None, "Card is locked");
-    assert!(leverage >= 1 && leverage <= 100, "Invalid leverage");
-    assert!(power_staked > 0, "Power staked must be positive");
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
+    if !(1..=100).contains(&leverage) {
+        return Err(NFTError::InvalidLeverage);
+    }
+    if power_staked == 0 {
+        return Err(NFTError::InvalidAmount);
+    }
     let config = read_config(&env);
 
     // Deduct fee and staked POWER
-    let power_fee = config.power_action_fee * power_staked / 100;
-    assert!(nft.power >= power_staked + power_fee, "Insufficient POWER");
+    let power_fee = read_action_fees(&env, &Action::Fight).power_action_fee * power_staked / 100;
     nft.power = nft
         .power
         .checked_sub(power_staked + power_fee)
-        .expect("Insufficient POWER");
+        .ok_or(NFTError::InsufficientPower)?;
 
-    let mut balance = read_balance(&env);
 
     // Calculate position
     let power_to_usdc_rate = config.power_to_usdc_rate;
@@ -252,18 +296,12 @@ pub fn open_position(
     let position_size = margin_usdc * leverage as i128;
 
     // Get currency price from oracle (1)
-    let mut trigger_price = 0;
-
     #[cfg(not(test))]
-    {
-        trigger_price =
-            get_currency_price(env.clone(), config.oracle_contract_id, currency.clone());
-    }
+    let trigger_price =
+        get_currency_price(env.clone(), config.oracle_contract_id.clone(), currency.clone())?;
     #[cfg(test)]
-    {
-        // Provide a deterministic mock price during tests
-        trigger_price = 1000;
-    }
+    // Provide a deterministic mock price during tests
+    let trigger_price: i128 = 1000;
     log!(&env, "fight >> trigger_price = ", trigger_price);
     // #[cfg(test)]
     // {
@@ -273,20 +311,16 @@ pub fn open_position(
 
     // let trigger_price = 8382580000; // Mock price for tests (83,825.8 USDC)
 
-    // Enhanced oracle price validation
-    assert!(trigger_price > 0, "Invalid oracle price: must be positive");
-    assert!(trigger_price < i128::MAX / 100, "Oracle price exceeds maximum");
-    
-    // TODO: Add staleness check when timestamp is available from oracle
-    // assert!(price_timestamp > env.ledger().timestamp() - 3600, "Oracle price too stale");
+    // Price bounds and staleness are checked by get_currency_price
 
     let amount_asset = position_size
         .checked_mul(1000000)
-        .expect("Overflow")
+        .ok_or(NFTError::Overflow)?
         / trigger_price;
 
     // Store fight
     nft.locked_by_action = Action::Fight;
+    let card_power = nft.power;
     write_nft(&env, owner.clone(), token_id.clone(), nft);
     write_fight(
         env.clone(),
@@ -296,7 +330,7 @@ pub fn open_position(
         Fight {
             owner: owner.clone(),
             category,
-            token_id,
+            token_id: token_id.clone(),
             currency,
             power: power_staked,
             trigger_price,
@@ -311,8 +345,19 @@ pub fn open_position(
     let terry_to_haw_ai = terry_reward * config.haw_ai_percentage as i128 / 100;
     
     mint_terry(&env, owner.clone(), terry_reward);
-    balance.haw_ai_terry += terry_to_haw_ai;
     
+    emit_fight_open(
+        &env,
+        &owner,
+        &CardEvent {
+            power: power_staked,
+            power_fee,
+            terry: terry_reward,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
+
     // Send power fee and terry to haw_ai_pot
     crate::pot::management::accumulate_pot_internal(
         &env, 
@@ -323,23 +368,30 @@ pub fn open_position(
         Some(Action::Fight)
     );
     
-    write_balance(&env, &balance);
+    Ok(())
 }
 
-pub fn close_position(env: Env, user: Address, category: Category, token_id: TokenId) {
+pub fn close_position(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+) -> Result<(), NFTError> {
     user.require_auth();
     let owner = read_user(&env, user.clone()).owner;
-    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
+    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
     log!(&env, "read nft = ", nft.clone());
+    if nft.locked_by_action != Action::Fight {
+        return Err(NFTError::FightNotFound);
+    }
     let fight = read_fight(
         env.clone(),
         owner.clone(),
         category.clone(),
         token_id.clone(),
-    );
+    )?;
     log!(&env, "read fight = ", fight.clone());
     let config = read_config(&env);
-    let mut balance = read_balance(&env);
 
     // Deduct fee
     // let power_fee = config.power_action_fee * fight.power / 100;
@@ -351,18 +403,15 @@ pub fn close_position(env: Env, user: Address, category: Category, token_id: Tok
     let power_to_usdc_rate = config.power_to_usdc_rate;
     let margin_usdc = (fight.power as i128) * power_to_usdc_rate / 10000;
     let position_size = margin_usdc * fight.leverage as i128;
-    let mut current_price = 0;
     #[cfg(not(test))]
-    {
-        current_price = get_currency_price(env.clone(), config.oracle_contract_id, fight.currency);
-    }
+    let current_price =
+        get_currency_price(env.clone(), config.oracle_contract_id.clone(), fight.currency.clone())?;
     #[cfg(test)]
-    {
-        current_price = 86000; // Mock price for tests (86,000 USDC)
-    }
+    let current_price: i128 = 86000; // Mock price for tests (86,000 USDC)
     log!(&env, "current asset price", current_price.clone());
-    assert!(current_price > 0, "Invalid oracle price");
-    assert!(fight.trigger_price > 0, "Invalid trigger price");
+    if fight.trigger_price <= 0 {
+        return Err(NFTError::InvalidOraclePrice);
+    }
     let pnl_usdc = position_size * (current_price - fight.trigger_price) / fight.trigger_price;
     let pnl_usdc = if fight.side_position == SidePosition::Long { pnl_usdc } else { -1 * pnl_usdc };
     let pnl_power = pnl_usdc * 10000 / power_to_usdc_rate;
@@ -390,7 +439,6 @@ pub fn close_position(env: Env, user: Address, category: Category, token_id: Tok
             
             // Send haw_ai's share to pot
             if profit_to_haw_ai > 0 {
-                balance.haw_ai_power += profit_to_haw_ai as u32;
                 crate::pot::management::accumulate_pot_internal(
                     &env,
                     0,
@@ -411,8 +459,9 @@ pub fn close_position(env: Env, user: Address, category: Category, token_id: Tok
 
     log!(&env, "power calculation: nft.power =", nft.power, "final_power =", final_power);
 
+    let card_power = final_power.min(card_metadata.max_power);
     if final_power == 0 {
-        remove_owner_card(&env, user.clone(), token_id.clone());
+        remove_owner_card(&env, user.clone(), token_id.clone())?;
         remove_nft(&env, user.clone(), token_id.clone());
     } else {
         nft.power = final_power.min(card_metadata.max_power);
@@ -421,15 +470,26 @@ pub fn close_position(env: Env, user: Address, category: Category, token_id: Tok
     }
     log!(&env, "remove fight", fight.token_id.clone());
     // Remove fight
-    remove_fight(env.clone(), owner.clone(), category.clone(), token_id);
+    remove_fight(env.clone(), owner.clone(), category.clone(), token_id.clone());
 
     // Mint TERRY rewards
     let terry_reward = config.terry_per_fight;
     let terry_to_haw_ai = terry_reward * config.haw_ai_percentage as i128 / 100;
     
     mint_terry(&env, owner.clone(), terry_reward);
-    balance.haw_ai_terry += terry_to_haw_ai;
     
+    emit_fight_close(
+        &env,
+        &owner,
+        pnl_power,
+        &CardEvent {
+            power: fight.power,
+            terry: terry_reward,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
+
     // Send terry to haw_ai_pot
     crate::pot::management::accumulate_pot_internal(
         &env,
@@ -440,5 +500,5 @@ pub fn close_position(env: Env, user: Address, category: Category, token_id: Tok
         Some(Action::Fight)
     );
     
-    write_balance(&env, &balance);
+    Ok(())
 }
@@ -3,12 +3,15 @@ use crate::{
     user_info::mint_terry,
     *,
 };
-use admin::{read_balance, read_config, write_balance};
+use admin::read_config;
 use nft_info::{read_nft, write_nft, Action, Category};
 use soroban_sdk::{contracttype, symbol_short, vec, Address, Env, Vec};
 use storage_types::{DataKey, TokenId, BorrowMeta, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
 use user_info::{read_user, write_user};
-use crate::event::{emit_lend, emit_borrow, emit_withdraw, emit_repay, emit_index_updated, emit_loan_touched, emit_loan_liquidated};
+use crate::error::NFTError;
+use crate::ledger::{post, Account, Asset};
+use crate::fees::read_action_fees;
+use crate::event::{card_event, emit_lend, emit_borrow, emit_withdraw, emit_repay, emit_index_updated, emit_loan_touched, emit_loan_liquidated, CardEvent};
 
 const SCALE: u64 = 1_000_000; // 6-decimal fixed point
 const APY_MIN: u64 = 0; // 0% APY
@@ -80,15 +83,20 @@ pub fn write_lending(
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 }
 
-pub fn read_lending(env: Env, user: Address, category: Category, token_id: TokenId) -> Lending {
+pub fn read_lending(env: Env, user: Address, category: Category, token_id: TokenId) -> Result<Lending, NFTError> {
     
     let owner = read_user(&env, user).owner;
 
     let key = DataKey::Lending(owner.clone(), category.clone(), token_id.clone());
+    let lending: Lending = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(NFTError::LendingNotFound)?;
     env.storage()
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
-    env.storage().persistent().get(&key).unwrap()
+    Ok(lending)
 }
 
 pub fn remove_lending(env: Env, user: Address, category: Category, token_id: TokenId) {
@@ -161,14 +169,19 @@ pub fn write_borrowing(
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
 }
 
-pub fn read_borrowing(env: Env, user: Address, category: Category, token_id: TokenId) -> Borrowing {
+pub fn read_borrowing(env: Env, user: Address, category: Category, token_id: TokenId) -> Result<Borrowing, NFTError> {
     let owner = read_user(&env, user).owner;
 
     let key = DataKey::Borrowing(owner.clone(), category.clone(), token_id.clone());
+    let borrowing: Borrowing = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(NFTError::BorrowingNotFound)?;
     env.storage()
         .persistent()
         .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
-    env.storage().persistent().get(&key).unwrap()
+    Ok(borrowing)
 }
 
 pub fn remove_borrowing(env: Env, user: Address, category: Category, token_id: TokenId) {
@@ -256,7 +269,13 @@ fn calculate_interest(principal: u64, apy: u64, duration_seconds: u64) -> u64 {
         / SCALE
 }
 
-pub fn lend(env: Env, user: Address, category: Category, token_id: TokenId, power: u32) {
+pub fn lend(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+    power: u32,
+) -> Result<(), NFTError> {
     // update accumulators
     {
         let mut st = read_state(&env);
@@ -274,28 +293,30 @@ pub fn lend(env: Env, user: Address, category: Category, token_id: TokenId, powe
     user.require_auth();
     let owner = read_user(&env, user).owner;
     let config = read_config(&env);
-    let power_fee: u32 = power.saturating_mul(config.power_action_fee) / 100;
+    let power_fee: u32 = power.saturating_mul(read_action_fees(&env, &Action::Lend).power_action_fee) / 100;
     let lend_amount: u32 = power.saturating_sub(power_fee);
-    assert!(
-        category == Category::Resource || category == Category::Leader,
-        "Invalid Category to lend"
-    );
+    if category != Category::Resource && category != Category::Leader {
+        return Err(NFTError::InvalidCategory);
+    }
+    if power == 0 {
+        return Err(NFTError::InvalidAmount);
+    }
 
-    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).unwrap();
-    assert!(
-        nft.locked_by_action == Action::None,
-        "Card is locked by another action"
-    );
-    assert!(nft.power >= power, "Exceed power amount to lend");
+    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
+    if nft.power < power {
+        return Err(NFTError::InsufficientPower);
+    }
 
     // Move gross power out of the card; fee goes to pot, net to pool (state.offer)
     nft.power = nft.power.saturating_sub(power);
     nft.locked_by_action = Action::Lend;
+    let card_power = nft.power;
     write_nft(&env.clone(), owner.clone(), token_id.clone(), nft);
 
-    let mut balance = read_balance(&env);
-
-    balance.haw_ai_power += power_fee;
+    post(&env, Asset::Power, Account::External, Account::HawAi, power_fee as i128, Action::Lend);
 
     let mut state = read_state(&env);
 
@@ -321,16 +342,39 @@ pub fn lend(env: Env, user: Address, category: Category, token_id: TokenId, powe
     );
 
     // Emit lend event
-    emit_lend(&env, &owner);
+    emit_lend(
+        &env,
+        &owner,
+        &CardEvent {
+            power,
+            power_fee,
+            terry: config.terry_per_lending,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     // Mint terry to user as rewards
     mint_terry(&env, owner.clone(), config.terry_per_lending);
 
-    balance.haw_ai_terry += config.terry_per_lending * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_lending * config.haw_ai_percentage as i128 / 100,
+        Action::Lend,
+    );
+    Ok(())
 }
 
-pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, power: u32) {
+pub fn borrow(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+    power: u32,
+) -> Result<(), NFTError> {
     // update accumulators
     {
         let mut st = read_state(&env);
@@ -349,28 +393,29 @@ pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, po
     let mut user = read_user(&env, user.clone());
     let owner = user.owner.clone();
     let config = read_config(&env);
-    let power_fee: u32 = power.saturating_mul(config.power_action_fee) / 100;
+    let power_fee: u32 = power.saturating_mul(read_action_fees(&env, &Action::Borrow).power_action_fee) / 100;
     let borrow_amount: u32 = power.saturating_sub(power_fee);
 
-    // Borrow > 0 validations
-    assert!(power > 0, "Invalid borrow: zero");
-    assert!(borrow_amount > 0, "Invalid borrow: net <= 0");
+    // Borrow > 0 validations (gross and net of fee)
+    if power == 0 || borrow_amount == 0 {
+        return Err(NFTError::InvalidAmount);
+    }
 
-    assert!(
-        category == Category::Resource || category == Category::Leader,
-        "Invalid Category to borrow"
-    );
+    if category != Category::Resource && category != Category::Leader {
+        return Err(NFTError::InvalidCategory);
+    }
 
-    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).unwrap();
-    assert!(
-        nft.locked_by_action == Action::None,
-        "Card is locked by another action"
-    );
+    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
 
     // config already read above
 
     let mut state = read_state(&env);
-    assert!(state.total_offer >= borrow_amount as u64, "Insufficient power to borrow");
+    if state.total_offer < borrow_amount as u64 {
+        return Err(NFTError::InsufficientLiquidity);
+    }
 
     // Pre-validate using hypothetical post-borrow state (no mutation yet)
     let offer_after = state.total_offer.saturating_sub(borrow_amount as u64);
@@ -392,7 +437,9 @@ pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, po
     let k_fp = (apy as u128)
         .saturating_mul(T_MAX_FP as u128)
         / (SCALE as u128);
-    assert!(k_fp < SCALE as u128, "Invalid horizon: APY*T_max >= 1");
+    if k_fp >= SCALE as u128 {
+        return Err(NFTError::InvalidHorizon);
+    }
     // Reserve = P * k / (1 - k)
     let reserve = (borrow_amount as u128)
         .saturating_mul(k_fp)
@@ -406,7 +453,9 @@ pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, po
         .saturating_add(reserve)
         .saturating_add(power_fee as u128)
         .saturating_add(buffer);
-    assert!(lhs <= nft.power as u128, "Exceeds collateral capacity");
+    if lhs > nft.power as u128 {
+        return Err(NFTError::ExceedsCollateral);
+    }
 
     // Now commit the state mutations after successful validation
     state.total_offer = offer_after;
@@ -418,12 +467,12 @@ pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, po
 
     // Deduct fee immediately from collateral card and lock
     nft.power = nft.power.saturating_sub(power_fee);
+    let card_power = nft.power;
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
-    let mut balance = read_balance(&env);
 
 
-    balance.haw_ai_power += power_fee;
+    post(&env, Asset::Power, Account::External, Account::HawAi, power_fee as i128, Action::Borrow);
 
     user.power += borrow_amount;
 
@@ -460,23 +509,41 @@ pub fn borrow(env: Env, user: Address, category: Category, token_id: TokenId, po
     }
 
     // Emit borrow event
-    emit_borrow(&env, &owner);
+    emit_borrow(
+        &env,
+        &owner,
+        &CardEvent {
+            power: borrow_amount,
+            power_fee,
+            terry: config.terry_per_lending,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     // Mint terry to user as rewards
     mint_terry(&env, owner.clone(), config.terry_per_lending);
 
-    balance.haw_ai_terry += config.terry_per_lending * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_lending * config.haw_ai_percentage as i128 / 100,
+        Action::Borrow,
+    );
+    Ok(())
 }
 
-pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: TokenId, power: u32) -> BorrowQuote {
+pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: TokenId, power: u32) -> Result<BorrowQuote, NFTError> {
     let owner = read_user(&env, user).owner;
     let config = read_config(&env);
-    let fee = power.saturating_mul(config.power_action_fee) / 100;
+    let power_action_fee = read_action_fees(&env, &Action::Borrow).power_action_fee;
+    let fee = power.saturating_mul(power_action_fee) / 100;
     let borrow_net: u32 = power.saturating_sub(fee);
 
     if power == 0 || borrow_net == 0 {
-        return BorrowQuote {
+        return Ok(BorrowQuote {
             allowed: false,
             reason: 1,
             apy: 0,
@@ -485,14 +552,14 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
             buffer: 0,
             borrow_net,
             max_suggested_gross: 0,
-        };
+        });
     }
 
-    let nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
+    let nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
     let st = read_state(&env);
 
     if st.total_offer < borrow_net as u64 {
-        return BorrowQuote {
+        return Ok(BorrowQuote {
             allowed: false,
             reason: 2,
             apy: 0,
@@ -501,7 +568,7 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
             buffer: 0,
             borrow_net,
             max_suggested_gross: 0,
-        };
+        });
     }
 
     let offer_after = st.total_offer.saturating_sub(borrow_net as u64);
@@ -522,7 +589,7 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
         .saturating_mul(T_MAX_FP as u128)
         / (SCALE as u128);
     if k_fp >= SCALE as u128 {
-        return BorrowQuote {
+        return Ok(BorrowQuote {
             allowed: false,
             reason: 4,
             apy,
@@ -531,7 +598,7 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
             buffer: 0,
             borrow_net,
             max_suggested_gross: 0,
-        };
+        });
     }
 
     let reserve = ((borrow_net as u128)
@@ -552,8 +619,8 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
             .saturating_sub(buffer);
         let borrow_net_max = (numer.saturating_mul((SCALE as u128).saturating_sub(k_fp))) / (SCALE as u128);
         let gross_suggested = ((borrow_net_max as u128) * 100u128)
-            / ((100u128).saturating_sub(config.power_action_fee as u128));
-        return BorrowQuote {
+            / ((100u128).saturating_sub(power_action_fee as u128));
+        return Ok(BorrowQuote {
             allowed: false,
             reason: 3,
             apy,
@@ -562,15 +629,15 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
             buffer: buffer as u64,
             borrow_net,
             max_suggested_gross: gross_suggested as u32,
-        };
+        });
     }
 
     // Also cap by liquidity (net)
     let borrow_net_cap = st.total_offer.min(borrow_net as u64) as u32;
     let gross_cap = ((borrow_net_cap as u128) * 100u128)
-        / ((100u128).saturating_sub(config.power_action_fee as u128));
+        / ((100u128).saturating_sub(power_action_fee as u128));
 
-    BorrowQuote {
+    Ok(BorrowQuote {
         allowed: true,
         reason: 0,
         apy,
@@ -579,10 +646,15 @@ pub fn borrow_quote(env: Env, user: Address, category: Category, token_id: Token
         buffer: buffer as u64,
         borrow_net,
         max_suggested_gross: gross_cap as u32,
-    }
+    })
 }
 
-pub fn repay(env: Env, user: Address, category: Category, token_id: TokenId) {
+pub fn repay(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+) -> Result<(), NFTError> {
     // update accumulators
     {
         let mut st = read_state(&env);
@@ -601,23 +673,21 @@ pub fn repay(env: Env, user: Address, category: Category, token_id: TokenId) {
     let mut user = read_user(&env, user);
     let owner = user.owner.clone();
 
-    assert!(
-        category == Category::Resource || category == Category::Leader,
-        "Invalid Category to repay"
-    );
+    if category != Category::Resource && category != Category::Leader {
+        return Err(NFTError::InvalidCategory);
+    }
 
-    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).unwrap();
-    assert!(
-        nft.locked_by_action == Action::Borrow,
-        "Card is not locked by borrow action"
-    );
+    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Borrow {
+        return Err(NFTError::BorrowingNotFound);
+    }
 
     let borrowing = read_borrowing(
         env.clone(),
         owner.clone(),
         category.clone(),
         token_id.clone(),
-    );
+    )?;
 
     let config = read_config(&env);
 
@@ -633,6 +703,10 @@ pub fn repay(env: Env, user: Address, category: Category, token_id: TokenId) {
         config.apy_alpha as u64,
     );
     let interest_amount = calculate_interest(borrowing.power as u64, apy, loan_duration_seconds);
+    let amount_due = borrowing.power + interest_amount as u32;
+    if user.power < amount_due {
+        return Err(NFTError::InsufficientPower);
+    }
     state.total_interest += interest_amount as u64;
     state.total_offer += borrowing.power as u64;
     state.total_borrowed_power -= borrowing.power as u64;
@@ -641,20 +715,25 @@ pub fn repay(env: Env, user: Address, category: Category, token_id: TokenId) {
     write_state(&env, &state);
 
     nft.locked_by_action = Action::None;
+    let card_power = nft.power;
 
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
-    assert!(
-        user.power >= borrowing.power + interest_amount as u32,
-        "Insufficient fund to repay"
-    );
-
-    user.power -= borrowing.power + interest_amount as u32;
+    user.power -= amount_due;
 
     write_user(&env, owner.clone(), user);
 
     // Emit repay event
-    emit_repay(&env, &owner);
+    emit_repay(
+        &env,
+        &owner,
+        &CardEvent {
+            power: amount_due,
+            terry: config.terry_per_lending,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     remove_borrowing(env.clone(), owner.clone(), category.clone(), token_id.clone());
     // cleanup meta and w_total
@@ -672,14 +751,21 @@ pub fn repay(env: Env, user: Address, category: Category, token_id: TokenId) {
     let config = read_config(&env);
     mint_terry(&env, owner.clone(), config.terry_per_lending);
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_terry += config.terry_per_lending * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_lending * config.haw_ai_percentage as i128 / 100,
+        Action::Borrow,
+    );
+    Ok(())
 }
 
-fn check_liquidations(env: Env) {
+fn check_liquidations(env: Env) -> Result<(), NFTError> {
     for borrowing in read_borrowings(env.clone()) {
-        let nft = read_nft(&env, borrowing.borrower.clone(), borrowing.token_id.clone()).unwrap();
+        let nft = read_nft(&env, borrowing.borrower.clone(), borrowing.token_id.clone())
+            .ok_or(NFTError::CardNotFound)?;
 
         let config = read_config(&env);
         let mut state = read_state(&env);
@@ -705,9 +791,10 @@ fn check_liquidations(env: Env) {
             );
         }
     }
+    Ok(())
 }
 
-fn liquidate(env: Env, user: Address, category: Category, token_id: TokenId) {
+fn liquidate(env: Env, user: Address, category: Category, token_id: TokenId) -> Result<(), NFTError> {
     user.require_auth();
     let user = read_user(&env, user);
     let owner = user.owner.clone();
@@ -717,9 +804,10 @@ fn liquidate(env: Env, user: Address, category: Category, token_id: TokenId) {
         owner.clone(),
         category.clone(),
         token_id.clone(),
-    );
+    )?;
     if borrowing.borrower == owner {
-        let nft = read_nft(&env, borrowing.borrower.clone(), borrowing.token_id.clone()).unwrap();
+        let nft = read_nft(&env, borrowing.borrower.clone(), borrowing.token_id.clone())
+            .ok_or(NFTError::CardNotFound)?;
 
         let config = read_config(&env);
         let mut state = read_state(&env);
@@ -745,6 +833,7 @@ fn liquidate(env: Env, user: Address, category: Category, token_id: TokenId) {
             );
         }
     }
+    Ok(())
 }
 
 /// Materialize pending haircuts for a batch of loans (permissionless keeper)
@@ -771,24 +860,29 @@ pub fn touch_loans(env: Env, loans: Vec<(Address, Category, TokenId)>) {
             let mut ownership_lost = false;
             if meta.reserve_remaining == 0 {
                 if let Some(mut nft) = read_nft(&env, addr.clone(), tid.clone()) {
-                    if nft.power > 0 {
-                        let cut = haircut.min(nft.power);
-                        nft.power = nft.power.saturating_sub(cut);
+                    let cut = haircut.min(nft.power);
+                    if cut > 0 {
+                        nft.power -= cut;
                         write_nft(&env, addr.clone(), tid.clone(), nft.clone());
                     }
                     if nft.power == 0 {
                         ownership_lost = true;
-                        emit_loan_liquidated(&env, &addr);
+                        emit_loan_liquidated(&env, &addr, &cat, &tid, cut);
                     }
                 }
             }
-            emit_loan_touched(&env, &addr, haircut, meta.reserve_remaining, ownership_lost);
+            emit_loan_touched(&env, &addr, &tid, haircut, meta.reserve_remaining, ownership_lost);
         }
     }
     write_state(&env, &state);
 }
 
-pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId) {
+pub fn withdraw(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+) -> Result<(), NFTError> {
     // update accumulators
     {
         let mut st = read_state(&env);
@@ -807,23 +901,21 @@ pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId)
     let mut user = read_user(&env, user);
     let owner = user.owner.clone();
 
-    assert!(
-        category == Category::Resource || category == Category::Leader,
-        "Invalid Category to withdraw"
-    );
+    if category != Category::Resource && category != Category::Leader {
+        return Err(NFTError::InvalidCategory);
+    }
 
-    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).unwrap();
-    assert!(
-        nft.locked_by_action == Action::Lend,
-        "Card is not locked by lend action"
-    );
+    let mut nft = read_nft(&env.clone(), owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Lend {
+        return Err(NFTError::LendingNotFound);
+    }
 
     let lending = read_lending(
         env.clone(),
         owner.clone(),
         category.clone(),
         token_id.clone(),
-    );
+    )?;
 
     let config = read_config(&env);
 
@@ -845,15 +937,13 @@ pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId)
         if state.w_total > 0 {
             let d_l = ((deficit as u128) * (SCALE as u128) / (state.w_total as u128)) as u64;
             state.l_index = state.l_index.saturating_add(d_l);
-            emit_index_updated(&env, state.l_index, d_l, deficit as u64, state.w_total);
+            emit_index_updated(&env, &owner, &token_id, interest_amount, d_l, deficit, &state);
         }
         state.total_interest = 0;
     } else {
         state.total_interest -= interest_amount;
     }
 
-    state = read_state(&env);
-
     state.total_offer -= lending.power as u64;
 
     write_state(&env, &state);
@@ -861,10 +951,11 @@ pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId)
     // Return principal_net to the same card and unlock
     nft.power = nft.power.saturating_add(lending.power);
     nft.locked_by_action = Action::None;
+    let card_power = nft.power;
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
-    let power_fee: u32 =
-        (interest_amount.saturating_mul(config.power_action_fee as u64) / 100) as u32;
+    let lend_fee = read_action_fees(&env, &Action::Lend).power_action_fee;
+    let power_fee: u32 = (interest_amount.saturating_mul(lend_fee as u64) / 100) as u32;
     let reward_interest: u64 = interest_amount.saturating_sub(power_fee as u64);
 
     user.power += reward_interest as u32;
@@ -872,7 +963,17 @@ pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId)
     write_user(&env, owner.clone(), user);
 
     // Emit withdraw event
-    emit_withdraw(&env, &owner);
+    emit_withdraw(
+        &env,
+        &owner,
+        &CardEvent {
+            power: lending.power + reward_interest as u32,
+            power_fee,
+            terry: config.terry_per_lending,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     remove_lending(env.clone(), owner.clone(), category, token_id);
 
@@ -880,10 +981,16 @@ pub fn withdraw(env: Env, user: Address, category: Category, token_id: TokenId)
     let config = read_config(&env);
     mint_terry(&env, owner.clone(), config.terry_per_lending);
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_power += power_fee;
-    balance.haw_ai_terry += config.terry_per_lending * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(&env, Asset::Power, Account::External, Account::HawAi, power_fee as i128, Action::Lend);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_lending * config.haw_ai_percentage as i128 / 100,
+        Action::Lend,
+    );
+    Ok(())
 }
 
 pub fn get_current_apy(env: Env) -> u64 {
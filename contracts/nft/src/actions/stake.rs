@@ -1,10 +1,15 @@
 use crate::{user_info::mint_terry, *};
-use admin::{read_balance, read_config, read_state, write_balance, write_state};
+use admin::{read_config, read_state, write_state};
 use nft_info::{read_nft, write_nft, Action, Category};
 use soroban_sdk::{contracttype, vec, Address, Env, Vec};
 use storage_types::{DataKey, TokenId, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
 use user_info::read_user;
-use crate::event::{emit_stake, emit_stake_increased, emit_unstake};
+use crate::error::NFTError;
+use crate::ledger::{post, Account, Asset};
+use crate::fees::read_action_fees;
+use crate::governance::weights;
+use crate::metadata::read_metadata;
+use crate::event::{card_event, emit_stake, emit_stake_increased, emit_unstake, CardEvent};
 
 #[contracttype]
 #[derive(Clone, PartialEq)]
@@ -21,6 +26,8 @@ pub struct Stake {
 pub fn write_stake(env: &Env, user: Address, category: Category, token_id: TokenId, stake: Stake) {
     let owner = read_user(&env, user).owner;
     let key = DataKey::Stake(owner.clone(), category.clone(), token_id.clone());
+    let old_power = env.storage().persistent().get::<_, Stake>(&key).map_or(0, |old| old.power);
+    weights::adjust_staked(env, &owner, old_power, stake.power);
     env.storage().persistent().set(&key, &stake);
     env.storage()
         .persistent()
@@ -55,6 +62,8 @@ pub fn remove_stake(env: &Env, user: Address, category: Category, token_id: Toke
     let owner = read_user(&env, user).owner;
 
     let key = DataKey::Stake(owner.clone(), category.clone(), token_id.clone());
+    let old_power = env.storage().persistent().get::<_, Stake>(&key).map_or(0, |old| old.power);
+    weights::adjust_staked(env, &owner, old_power, 0);
     env.storage().persistent().remove(&key);
     if env.storage().persistent().has(&key) {
         env.storage().persistent().extend_ttl(
@@ -84,9 +93,14 @@ pub fn remove_stake(env: &Env, user: Address, category: Category, token_id: Toke
     }
 }
 
-pub fn read_stake(env: &Env, user: Address, category: Category, token_id: TokenId) -> Stake {
+pub fn read_stake(env: &Env, user: Address, category: Category, token_id: TokenId) -> Result<Stake, NFTError> {
     let owner = read_user(&env, user).owner;
     let key = DataKey::Stake(owner, category, token_id);
+    let stake: Stake = env
+        .storage()
+        .persistent()
+        .get(&key)
+        .ok_or(NFTError::StakeNotFound)?;
     #[cfg(not(test))]
     {
         env.storage().persistent().extend_ttl(
@@ -95,41 +109,54 @@ pub fn read_stake(env: &Env, user: Address, category: Category, token_id: TokenI
             BALANCE_BUMP_AMOUNT,
         );
     }
-    env.storage().persistent().get(&key).unwrap()
+    Ok(stake)
 }
 
-pub fn stake(env: Env, user: Address, category: Category, token_id: TokenId, period_index: u32) {
+pub fn stake(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+    period_index: u32,
+) -> Result<(), NFTError> {
     user.require_auth();
-    assert!(
-        category == Category::Skill || category == Category::Leader,
-        "Invalid Category to stake"
-    );
+    if category != Category::Skill && category != Category::Leader {
+        return Err(NFTError::InvalidCategory);
+    }
     let owner = read_user(&env, user).owner;
 
-    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
-    assert!(nft.locked_by_action == Action::None, "Locked NFT");
+    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::None {
+        return Err(NFTError::CardLocked);
+    }
 
     let config = read_config(&env);
-    // Validate period index bounds to avoid panic
-    assert!(period_index < config.stake_periods.len(), "Invalid period index");
-    assert!(period_index < config.stake_interest_percentages.len(), "Invalid period index");
-    let power_fee = config.power_action_fee * nft.power / 100;
+    let stake_period = config
+        .stake_periods
+        .get(period_index)
+        .ok_or(NFTError::InvalidStakePeriod)?;
+    let stake_interest = config
+        .stake_interest_percentages
+        .get(period_index)
+        .ok_or(NFTError::InvalidStakePeriod)?;
+    let power_fee = read_action_fees(&env, &Action::Stake).power_action_fee * nft.power / 100;
 
     nft.locked_by_action = Action::Stake;
     let staked_power = nft
         .power
         .checked_sub(power_fee)
-        .expect("Insufficient POWER for fee");
+        .ok_or(NFTError::InsufficientPower)?;
     nft.power = 0;
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_power += power_fee;
-    write_balance(&env, &balance);
+    post(&env, Asset::Power, Account::External, Account::HawAi, power_fee as i128, Action::Stake);
 
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
-    let stake_period = config.stake_periods.get(period_index).unwrap();
-    let stake_interest = config.stake_interest_percentages.get(period_index).unwrap();
+    let staked_time: u32 = env
+        .ledger()
+        .timestamp()
+        .try_into()
+        .map_err(|_| NFTError::Overflow)?;
 
     write_stake(
         &env,
@@ -143,21 +170,27 @@ pub fn stake(env: Env, user: Address, category: Category, token_id: TokenId, per
             power: staked_power,
             period: stake_period,
             interest_percentage: stake_interest,
-            staked_time: env
-                .ledger()
-                .timestamp()
-                .try_into()
-                .expect("Timestamp exceeds u32 limit"),
+            staked_time,
         },
     );
 
     // Emit stake event
-    emit_stake(&env, &owner);
+    emit_stake(
+        &env,
+        &owner,
+        &CardEvent {
+            power: staked_power + power_fee,
+            power_fee,
+            card_power: 0,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     let mut state = read_state(&env);
     state.total_staked_power += staked_power as u64;
 
     write_state(&env, &state);
+    Ok(())
 }
 
 pub fn increase_stake_power(
@@ -166,41 +199,53 @@ pub fn increase_stake_power(
     category: Category,
     token_id: TokenId,
     increase_power: u32,
-) {
+) -> Result<(), NFTError> {
     user.require_auth();
+    increase_stake_power_internal(env, user, category, token_id, increase_power)
+}
+
+/// `increase_stake_power` for flows that have already authorized the user.
+pub fn increase_stake_power_internal(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+    increase_power: u32,
+) -> Result<(), NFTError> {
     let owner = read_user(&env, user).owner;
-    
+
     // Input validation
-    assert!(increase_power > 0, "Increase power must be positive");
-    assert!(increase_power <= u32::MAX / 2, "Increase power too large");
+    if increase_power == 0 || increase_power > u32::MAX / 2 {
+        return Err(NFTError::InvalidAmount);
+    }
 
-    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
-    assert!(nft.locked_by_action == Action::Stake, "Can't find staked");
-    assert!(nft.power >= increase_power, "Insufficient NFT power");
+    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Stake {
+        return Err(NFTError::StakeNotFound);
+    }
+    if nft.power < increase_power {
+        return Err(NFTError::InsufficientPower);
+    }
 
-    let mut stake = read_stake(&env, owner.clone(), category.clone(), token_id.clone());
-    
-    // Safe addition to prevent overflow
-    stake.power = stake.power.checked_add(increase_power)
-        .expect("Stake power overflow");
+    let mut stake = read_stake(&env, owner.clone(), category.clone(), token_id.clone())?;
 
     let config = read_config(&env);
-    let power_fee = config.power_action_fee.checked_mul(increase_power)
+    let power_fee = read_action_fees(&env, &Action::Stake)
+        .power_action_fee
+        .checked_mul(increase_power)
         .and_then(|v| v.checked_div(100))
-        .expect("Fee calculation overflow");
-    
-    // Safe subtraction to prevent underflow
-    stake.power = stake.power.checked_sub(power_fee)
-        .expect("Insufficient stake power for fee");
-
-    // Safe subtraction to prevent underflow
-    nft.power = nft.power.checked_sub(increase_power)
-        .expect("Insufficient NFT power");
+        .ok_or(NFTError::Overflow)?;
+
+    let staked_power = increase_power
+        .checked_sub(power_fee)
+        .ok_or(NFTError::InsufficientPower)?;
+    add_stake_power(&env, &mut stake, staked_power)?;
+
+    nft.power -= increase_power;
+    let card_power = nft.power;
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_power += power_fee;
-    write_balance(&env, &balance);
+    post(&env, Asset::Power, Account::External, Account::HawAi, power_fee as i128, Action::Stake);
 
     write_stake(
         &env,
@@ -211,40 +256,119 @@ pub fn increase_stake_power(
     );
 
     // Emit stake increased event
-    emit_stake_increased(&env, &owner);
+    emit_stake_increased(
+        &env,
+        &owner,
+        &CardEvent {
+            power: increase_power,
+            power_fee,
+            terry: config.terry_per_stake,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     // Mint terry to user as rewards
     mint_terry(&env, owner, config.terry_per_stake);
 
-    balance.haw_ai_terry += config.terry_per_stake * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_stake * config.haw_ai_percentage as i128 / 100,
+        Action::Stake,
+    );
+    Ok(())
 }
 
-pub fn unstake(env: Env, user: Address, category: Category, token_id: TokenId) {
-    user.require_auth();
-    let owner = read_user(&env, user).owner;
-    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).unwrap();
-    assert!(nft.locked_by_action == Action::Stake, "Can't find staked");
-
-    let current_time: u32 = env
+/// Grows `stake` by `power` and restarts its period, so the added POWER is
+/// locked (and earns interest) for a full period like the rest of the stake.
+fn add_stake_power(env: &Env, stake: &mut Stake, power: u32) -> Result<(), NFTError> {
+    stake.power = stake.power.checked_add(power).ok_or(NFTError::Overflow)?;
+    stake.staked_time = env
         .ledger()
         .timestamp()
         .try_into()
-        .expect("Timestamp exceeds u32 limit");
+        .map_err(|_| NFTError::Overflow)?;
+
+    let mut state = read_state(env);
+    state.total_staked_power += power as u64;
+    write_state(env, &state);
+    Ok(())
+}
 
-    let stake = read_stake(&env, owner.clone(), category.clone(), token_id.clone());
+/// Stakes up to `power` claimed pot POWER onto an active stake. Unlike
+/// `increase_stake_power` it takes no stake fee and mints no `terry_per_stake`,
+/// but the card may not pass its metadata `max_power` and the stake period
+/// restarts. Returns the POWER staked.
+pub fn compound_stake_power(
+    env: &Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+    power: u32,
+) -> Result<u32, NFTError> {
+    let owner = read_user(env, user).owner;
+    let nft = read_nft(env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Stake {
+        return Err(NFTError::StakeNotFound);
+    }
+    let mut stake = read_stake(env, owner.clone(), category.clone(), token_id.clone())?;
+
+    let room = read_metadata(env, token_id.0)
+        .max_power
+        .saturating_sub(nft.power)
+        .saturating_sub(stake.power);
+    let power = power.min(room);
+    if power == 0 {
+        return Ok(0);
+    }
+    add_stake_power(env, &mut stake, power)?;
+    write_stake(env, owner.clone(), category, token_id.clone(), stake);
+
+    emit_stake_increased(
+        env,
+        &owner,
+        &CardEvent {
+            power,
+            card_power: nft.power,
+            ..card_event(env, &token_id)
+        },
+    );
+    Ok(power)
+}
+
+pub fn unstake(
+    env: Env,
+    user: Address,
+    category: Category,
+    token_id: TokenId,
+) -> Result<(), NFTError> {
+    user.require_auth();
+    let owner = read_user(&env, user).owner;
+    let mut nft = read_nft(&env, owner.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+    if nft.locked_by_action != Action::Stake {
+        return Err(NFTError::StakeNotFound);
+    }
+
+    let stake = read_stake(&env, owner.clone(), category.clone(), token_id.clone())?;
     #[cfg(not(test))]
     {
-        assert!(
-            stake.staked_time + stake.period <= current_time,
-            "Locked Period"
-        );
+        let current_time: u32 = env
+            .ledger()
+            .timestamp()
+            .try_into()
+            .map_err(|_| NFTError::Overflow)?;
+        if stake.staked_time + stake.period > current_time {
+            return Err(NFTError::StakeLocked);
+        }
     }
 
     let interest_amount = stake.power * stake.interest_percentage / 100;
-    let staked_power = stake.power;
     nft.power += stake.power + interest_amount;
     nft.locked_by_action = Action::None;
+    let card_power = nft.power;
 
     write_nft(&env, owner.clone(), token_id.clone(), nft);
 
@@ -254,14 +378,29 @@ pub fn unstake(env: Env, user: Address, category: Category, token_id: TokenId) {
     mint_terry(&env, owner.clone(), terry_amount);
 
     // Emit unstake event
-    emit_unstake(&env, &owner);
+    emit_unstake(
+        &env,
+        &owner,
+        &CardEvent {
+            power: stake.power + interest_amount,
+            terry: terry_amount + config.terry_per_stake,
+            card_power,
+            ..card_event(&env, &token_id)
+        },
+    );
 
     remove_stake(&env, owner.clone(), category.clone(), token_id.clone());
 
     // Mint terry to user as rewards
     mint_terry(&env, owner, config.terry_per_stake);
 
-    let mut balance = read_balance(&env);
-    balance.haw_ai_terry += config.terry_per_stake * config.haw_ai_percentage as i128 / 100;
-    write_balance(&env, &balance);
+    post(
+        &env,
+        Asset::Terry,
+        Account::External,
+        Account::HawAi,
+        config.terry_per_stake * config.haw_ai_percentage as i128 / 100,
+        Action::Stake,
+    );
+    Ok(())
 }
@@ -1,5 +1,13 @@
+use crate::error::NFTError;
+use crate::event::emit_state_updated;
+use crate::ledger::{read_account, Account};
 use crate::storage_types::*;
-use soroban_sdk::{symbol_short, Address, Env};
+use soroban_sdk::{symbol_short, Address, Env, FromVal, Map, Symbol, Val, Vec};
+
+/// Maximum protocol fee in basis points (50%).
+pub const MAX_DOGSTAR_FEE_PERCENTAGE: u32 = 5000;
+/// Maximum keeper reward in basis points (10%).
+pub const MAX_KEEPER_REWARD_BPS: u32 = 1000;
 
 pub fn has_administrator(e: &Env) -> bool {
     let key = DataKey::Admin;
@@ -26,11 +34,140 @@ pub fn is_whitelisted(e: &Env, member: &Address) -> bool {
 pub fn write_config(e: &Env, config: &Config) {
     let key: DataKey = DataKey::Config;
     e.storage().persistent().set(&key, config);
+    e.storage()
+        .persistent()
+        .set(&DataKey2::ConfigVersion, &CONFIG_VERSION);
+
+    // The first keeper round runs from the moment rounds are first configured
+    if config.round_duration > 0 && !e.storage().persistent().has(&DataKey2::RoundsStartedAt) {
+        e.storage()
+            .persistent()
+            .set(&DataKey2::RoundsStartedAt, &e.ledger().timestamp());
+    }
 }
 
+/// Start of the first keeper round: the initialization or config update that
+/// first set a `round_duration`.
+pub fn read_rounds_started_at(e: &Env) -> Option<u64> {
+    e.storage().persistent().get(&DataKey2::RoundsStartedAt)
+}
+
+fn read_config_version(e: &Env) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&DataKey2::ConfigVersion)
+        .unwrap_or(0)
+}
+
+/// Rewrites a config stored in the `LegacyConfig` layout in the current one.
+/// Returns false when the stored config is already current.
+pub fn migrate_config(e: &Env) -> bool {
+    if read_config_version(e) == CONFIG_VERSION {
+        return false;
+    }
+    let config = read_config(e);
+    write_config(e, &config);
+    true
+}
+
+/// Checks a config before it is stored, either at `initialize` or on update.
+pub fn validate_config(config: &Config) -> Result<(), NFTError> {
+    // Whole percentages (0-100)
+    for percentage in [
+        config.withdrawable_percentage,
+        config.burnable_percentage,
+        config.haw_ai_percentage,
+        config.burn_receive_percentage,
+    ] {
+        if percentage > 100 {
+            return Err(NFTError::InvalidPercentage);
+        }
+    }
+    // The borrow quote divides by (100 - power_action_fee)
+    if config.power_action_fee >= 100 {
+        return Err(NFTError::FeeTooHigh);
+    }
+    if config.dogstar_fee_percentage > MAX_DOGSTAR_FEE_PERCENTAGE {
+        return Err(NFTError::FeeTooHigh);
+    }
+    if config.keeper_reward_bps > MAX_KEEPER_REWARD_BPS {
+        return Err(NFTError::FeeTooHigh);
+    }
+    // A sum that overflows is as invalid as one above 100%
+    let retains_too_much = match config
+        .pot_rollover_bps
+        .checked_add(config.jackpot_reserve_bps)
+    {
+        Some(retained_bps) => retained_bps > 10000,
+        None => true,
+    };
+    if retains_too_much || config.early_claim_penalty_bps > 10000
+    {
+        return Err(NFTError::InvalidPercentage);
+    }
+
+    if config.stake_periods.is_empty()
+        || config.stake_periods.len() != config.stake_interest_percentages.len()
+    {
+        return Err(NFTError::StakeScheduleMismatch);
+    }
+
+    // close_position divides by power_to_usdc_rate
+    if config.power_to_usdc_rate <= 0 {
+        return Err(NFTError::InvalidConversionRate);
+    }
+
+    for amount in [
+        config.terry_per_power,
+        config.terry_per_deck,
+        config.terry_per_fight,
+        config.terry_per_lending,
+        config.terry_per_stake,
+    ] {
+        if amount < 0 {
+            return Err(NFTError::NegativeAmount);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the config, filling the settings a `LegacyConfig` lacks with their
+/// disabled defaults until `migrate` rewrites it.
 pub fn read_config(e: &Env) -> Config {
     let key = DataKey::Config;
-    e.storage().persistent().get(&key).unwrap()
+    if read_config_version(e) == CONFIG_VERSION {
+        return e.storage().persistent().get(&key).unwrap();
+    }
+    let legacy: LegacyConfig = e.storage().persistent().get(&key).unwrap();
+    Config {
+        xtar_token: legacy.xtar_token,
+        oracle_contract_id: legacy.oracle_contract_id,
+        withdrawable_percentage: legacy.withdrawable_percentage,
+        burnable_percentage: legacy.burnable_percentage,
+        haw_ai_percentage: legacy.haw_ai_percentage,
+        terry_per_power: legacy.terry_per_power,
+        stake_periods: legacy.stake_periods,
+        stake_interest_percentages: legacy.stake_interest_percentages,
+        power_action_fee: legacy.power_action_fee,
+        burn_receive_percentage: legacy.burn_receive_percentage,
+        terry_per_deck: legacy.terry_per_deck,
+        terry_per_fight: legacy.terry_per_fight,
+        terry_per_lending: legacy.terry_per_lending,
+        terry_per_stake: legacy.terry_per_stake,
+        apy_alpha: legacy.apy_alpha,
+        power_to_usdc_rate: legacy.power_to_usdc_rate,
+        dogstar_fee_percentage: legacy.dogstar_fee_percentage,
+        param_change_delay: 0,
+        round_duration: 0,
+        keeper_reward_bps: 0,
+        pot_rollover_bps: 0,
+        jackpot_reserve_bps: 0,
+        jackpot_interval: 0,
+        claim_expiry_rounds: 0,
+        vesting_period: 0,
+        early_claim_penalty_bps: 0,
+    }
 }
 
 pub fn write_balance(e: &Env, balance: &Balance) {
@@ -39,23 +176,33 @@ pub fn write_balance(e: &Env, balance: &Balance) {
     e.storage().persistent().set(&key, balance);
 }
 
+/// Reads the balance record. Its admin and haw_ai amounts are views of the ledger;
+/// only `total_deck_power` is stored.
 pub fn read_balance(e: &Env) -> Balance {
     let key = DataKey::Balance;
-    e.storage().persistent().get(&key).unwrap_or(Balance {
+    let mut balance = e.storage().persistent().get(&key).unwrap_or(Balance {
         admin_terry: 0,
         admin_power: 0,
         haw_ai_terry: 0,
         haw_ai_power: 0,
         haw_ai_xtar: 0,
         total_deck_power: 0,
-    })
+    });
+    let admin = read_account(e, Account::AdminWithdrawable);
+    let haw_ai = read_account(e, Account::HawAi);
+    balance.admin_terry = admin.terry;
+    balance.admin_power = admin.power as u32;
+    balance.haw_ai_terry = haw_ai.terry;
+    balance.haw_ai_power = haw_ai.power as u32;
+    balance.haw_ai_xtar = haw_ai.xtar;
+    balance
 }
 
 pub fn write_state(e: &Env, state: &State) {
     let key = DataKey::State;
     e.storage().persistent().set(&key, state);
 
-    e.events().publish((symbol_short!("state"),), state.clone());
+    emit_state_updated(e, state);
 }
 
 pub fn read_state(e: &Env) -> State {
@@ -105,25 +252,23 @@ pub fn get_and_increase_level_id(env: &Env) -> u32 {
     prev + 1
 }
 
-// Vault management functions
-pub fn write_contract_vault(e: &Env, vault: &ContractVault) {
-    let key = DataKey::ContractVault;
-    e.storage().persistent().set(&key, vault);
-}
-
+/// Vault view over the ledger: undistributed pot, unreleased Dogstar fees and
+/// unclaimed player shares.
 pub fn read_contract_vault(e: &Env) -> ContractVault {
-    let key = DataKey::ContractVault;
-    e.storage().persistent().get(&key).unwrap_or(ContractVault {
-        haw_ai_pot_terry: 0,
-        haw_ai_pot_power: 0,
-        haw_ai_pot_xtar: 0,
-        dogstar_terry: 0,
-        dogstar_power: 0,
-        dogstar_xtar: 0,
-        total_claimable_terry: 0,
-        total_claimable_power: 0,
-        total_claimable_xtar: 0,
-    })
+    let pot = read_account(e, Account::Pot);
+    let dogstar = read_account(e, Account::DogstarFees);
+    let claimable = read_account(e, Account::Claimable);
+    ContractVault {
+        haw_ai_pot_terry: pot.terry,
+        haw_ai_pot_power: pot.power as u32,
+        haw_ai_pot_xtar: pot.xtar,
+        dogstar_terry: dogstar.terry,
+        dogstar_power: dogstar.power as u32,
+        dogstar_xtar: dogstar.xtar,
+        total_claimable_terry: claimable.terry,
+        total_claimable_power: claimable.power as u32,
+        total_claimable_xtar: claimable.xtar,
+    }
 }
 
 pub fn write_user_claimable_balance(e: &Env, user: &Address, balance: &UserClaimableBalance) {
@@ -131,15 +276,32 @@ pub fn write_user_claimable_balance(e: &Env, user: &Address, balance: &UserClaim
     e.storage().persistent().set(&key, balance);
 }
 
+/// Balances written before rewards were tracked by round read with no round
+/// entries, so what they hold counts as vested and never expires.
 pub fn read_user_claimable_balance(e: &Env, user: &Address) -> UserClaimableBalance {
     let key = DataKey::UserClaimableBalance(user.clone());
-    e.storage().persistent().get(&key).unwrap_or(UserClaimableBalance {
-        terry: 0,
-        power: 0,
-        xtar: 0,
-        last_claim_round: 0,
-        last_claim_timestamp: 0,
-    })
+    let Some(stored) = e.storage().persistent().get::<_, Map<Symbol, Val>>(&key) else {
+        return UserClaimableBalance {
+            terry: 0,
+            power: 0,
+            xtar: 0,
+            last_claim_round: 0,
+            last_claim_timestamp: 0,
+            rounds: Vec::new(e),
+        };
+    };
+    if stored.contains_key(symbol_short!("rounds")) {
+        return UserClaimableBalance::from_val(e, &stored.to_val());
+    }
+    let legacy = LegacyClaimableBalance::from_val(e, &stored.to_val());
+    UserClaimableBalance {
+        terry: legacy.terry,
+        power: legacy.power,
+        xtar: legacy.xtar,
+        last_claim_round: legacy.last_claim_round,
+        last_claim_timestamp: legacy.last_claim_timestamp,
+        rounds: Vec::new(e),
+    }
 }
 
 pub fn write_dogstar_claimable(e: &Env, balance: &UserClaimableBalance) {
@@ -147,13 +309,21 @@ pub fn write_dogstar_claimable(e: &Env, balance: &UserClaimableBalance) {
     e.storage().persistent().set(&key, balance);
 }
 
+/// Reads the Dogstar claim record. Amounts come from the ledger; only the claim
+/// round and timestamp are stored.
 pub fn read_dogstar_claimable(e: &Env) -> UserClaimableBalance {
     let key = DataKey::DogstarClaimableBalance;
-    e.storage().persistent().get(&key).unwrap_or(UserClaimableBalance {
+    let mut claimable = e.storage().persistent().get(&key).unwrap_or(UserClaimableBalance {
         terry: 0,
         power: 0,
         xtar: 0,
         last_claim_round: 0,
         last_claim_timestamp: 0,
-    })
+        rounds: Vec::new(e),
+    });
+    let account = read_account(e, Account::DogstarClaimable);
+    claimable.terry = account.terry;
+    claimable.power = account.power as u32;
+    claimable.xtar = account.xtar;
+    claimable
 }
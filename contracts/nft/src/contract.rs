@@ -1,47 +1,69 @@
 //! This contract demonstrates a sample implementation of the Soroban token
 //! interface.
 
-use crate::actions::{read_deck, deck::read_decks};
+use crate::actions::read_deck;
 use crate::actions::{
     burn, deck, fight, lending,
     lending::{Borrowing, Lending},
     stake, SidePosition,
 };
 use crate::admin::{
-    add_level, has_administrator, read_administrator, read_balance, read_config, read_state,
-    update_level, write_administrator, write_balance, write_config, read_contract_vault,
-    write_contract_vault, read_user_claimable_balance, write_user_claimable_balance,
-    read_dogstar_claimable, write_dogstar_claimable,
+    add_level, has_administrator, migrate_config, read_rounds_started_at, read_administrator, read_balance, read_config, read_state,
+    update_level, validate_config, write_administrator, write_balance, write_config,
+    read_contract_vault, read_user_claimable_balance, write_user_claimable_balance,
 };
-use crate::error::NFTError;
+use crate::error::{EconomyError, NFTError};
 use crate::event::*;
-use crate::metadata::{read_metadata, write_metadata, CardMetadata};
+use crate::fees::{self, ActionFees, FeeRecipient, FeeScheduleEntry};
+use crate::governance::proposal::{self, GovernanceSettings, Proposal, ProposalStatus, Vote};
+use crate::governance::timelock::{self, ensure_no_timelock, ConfigChange, QueuedChange};
+use crate::governance::weights;
+use crate::ledger::{self, post, post_all, Account, AccountBalance, Asset, JournalEntry, JournalRange};
+use crate::metadata::{has_metadata, read_metadata, write_metadata, CardMetadata};
 use crate::nft_info::{exists, read_nft, remove_nft, write_nft, Action, Card, Category, Currency};
+use crate::pot::audit::{self, SolvencyReport, TokenSolvencyReport};
+use crate::pot::compound::{self, AutoCompound};
+use crate::pot::distribution::{
+    accrue, close_period, read_claimable, read_effective_power, read_player_power_time,
+    read_power_time, read_reward_index, RewardIndex,
+};
+use crate::pot::expiry;
 use crate::pot::management::*;
-use crate::pot::reward::*;
+use crate::pot::modes::{self, read_distribution_mode, DistributionMode};
+use crate::pot::opening::{self, ensure_no_opening, OpeningProgress};
+use crate::pot::reserve;
+use crate::pot::reward;
+use crate::pot::vesting::{self, VestingBalance};
+use crate::pot::tokens::{self, PotTokenBalance};
+use crate::curve::{self, PriceCurve};
+use crate::pricing::{self, MintQuote, PaymentToken, TemplatePrice};
+use crate::sale::{self, SaleConfig, SalePhase, TemplatePhase};
 use crate::storage_types::*;
+use crate::treasury::{self, TreasuryRecord};
 use crate::user_info::{
     add_card_to_owner, burn_terry, get_user_level, mint_terry, read_owner_card, read_user,
     write_owner_card, write_user,
 };
 
 use soroban_sdk::{
-    contract, contractimpl, token, Address, BytesN, Env, Symbol};
+    contract, contractimpl, panic_with_error, token, Address, BytesN, Env};
 use soroban_sdk::{vec, String, Vec};
-use soroban_token_sdk::TokenUtils;
 
 #[contract]
 pub struct NFT;
 
 #[contractimpl]
 impl NFT {
-    pub fn initialize(e: Env, admin: Address, config: Config) {
+    pub fn initialize(e: Env, admin: Address, config: Config) -> Result<(), NFTError> {
         // Check if the contract is already initialized
         if has_administrator(&e) {
-            panic!("already initialized");
+            return Err(NFTError::AlreadyInitialized);
         }
+        validate_config(&config)?;
         write_administrator(&e, &admin);
         write_config(&e, &config);
+        // Nothing to seed yet; marks voting weights as tracked from the start
+        weights::seed(&e);
         write_balance(
             &e,
             &Balance {
@@ -53,22 +75,6 @@ impl NFT {
                 total_deck_power: 0,
             },
         );
-        
-        // Initialize the contract vault
-        write_contract_vault(
-            &e,
-            &ContractVault {
-                haw_ai_pot_terry: 0,
-                haw_ai_pot_power: 0,
-                haw_ai_pot_xtar: 0,
-                dogstar_terry: 0,
-                dogstar_power: 0,
-                dogstar_xtar: 0,
-                total_claimable_terry: 0,
-                total_claimable_power: 0,
-                total_claimable_xtar: 0,
-            },
-        );
 
         let levels = vec![
             &e,
@@ -128,9 +134,23 @@ impl NFT {
             add_level(&e, level);
         }
 
-        // Emit initialization event
-        e.events()
-            .publish((Symbol::new(&e, "initialized"),), (admin,));
+        emit_initialized(&e, &admin);
+
+        Ok(())
+    }
+
+    pub fn update_config(e: Env, config: Config) -> Result<(), NFTError> {
+        let admin = read_administrator(&e);
+        admin.require_auth();
+
+        // With a timelock configured, every change goes through the queue
+        ensure_no_timelock(&read_config(&e))?;
+
+        validate_config(&config)?;
+        tokens::ensure_supported_policies(&e, &config);
+        write_config(&e, &config);
+        emit_config_updated(&e, &config);
+        Ok(())
     }
 
     pub fn add_new_level(e: Env, level: Level) {
@@ -151,28 +171,36 @@ impl NFT {
         mint_terry(&e, player, amount);
     }
 
-    pub fn batch_mint_terry(e: Env, to_addresses: Vec<Address>, amounts: Vec<i128>) {
+    pub fn batch_mint_terry(
+        e: Env,
+        to_addresses: Vec<Address>,
+        amounts: Vec<i128>,
+    ) -> Result<(), NFTError> {
         let admin = read_administrator(&e);
         admin.require_auth();
         if to_addresses.len() != amounts.len() {
-            panic!("Mismatched lengths of addresses and amounts");
+            return Err(NFTError::MismatchedLengths);
         }
-        
+
         // Define maximum mint amount per transaction (e.g., 1 billion)
         const MAX_MINT_AMOUNT: i128 = 1_000_000_000;
         const MAX_BATCH_SIZE: u32 = 100;
-        
-        assert!(to_addresses.len() <= MAX_BATCH_SIZE, "Batch size too large");
-        
+
+        if to_addresses.len() > MAX_BATCH_SIZE {
+            return Err(NFTError::BatchTooLarge);
+        }
+
         // Validate all amounts before processing
         for amount in amounts.iter() {
-            assert!(amount > 0, "Mint amount must be positive");
-            assert!(amount <= MAX_MINT_AMOUNT, "Mint amount exceeds maximum");
+            if amount <= 0 || amount > MAX_MINT_AMOUNT {
+                return Err(NFTError::InvalidAmount);
+            }
         }
 
         for (to, amount) in to_addresses.iter().zip(amounts.iter()) {
             mint_terry(&e, to, amount);
         }
+        Ok(())
     }
 
     pub fn terry_balance(e: Env, player: Address) -> i128 {
@@ -186,34 +214,45 @@ impl NFT {
         token_id: TokenId,
         card_level: u32,
         buy_currency: Currency,
-    ) {
+        max_price: Option<i128>,
+    ) -> Result<(), NFTError> {
         user.require_auth();
 
         let user: User = read_user(&env, user.clone());
         let to: Address = user.owner.clone();
         let user_level = get_user_level(&env, to.clone());
 
-        assert!(
-            user_level >= card_level,
-            "User level too low to mint this card"
-        );
-        assert!(
-            !Self::exists(&env, to.clone(), token_id.clone()),
-            "Token ID already exists"
-        );
+        if user_level < card_level {
+            return Err(NFTError::LevelTooLow);
+        }
+        if Self::exists(&env, to.clone(), token_id.clone()) {
+            return Err(NFTError::CardAlreadyOwned);
+        }
+        if !has_metadata(&env, token_id.0) {
+            return Err(NFTError::MetadataNotFound);
+        }
 
         let card_metadata = read_metadata(&env, token_id.clone().0);
+        // Sale failures keep their EconomyError codes
+        if let Err(error) = sale::record_mint(&env, token_id.0, &to) {
+            panic_with_error!(&env, error);
+        }
+        let quote = pricing::quote(&env, token_id.0, &card_metadata, &buy_currency)?;
+        // Reject the mint if the price rose above what the player agreed to
+        if max_price.is_some_and(|max| quote.price > max) {
+            panic_with_error!(&env, EconomyError::SlippageExceeded);
+        }
+
         let nft = Card {
             power: card_metadata.initial_power,
             locked_by_action: Action::None,
         };
         write_nft(&env, to.clone(), token_id.clone(), nft.clone());
 
-        add_card_to_owner(&env, token_id.clone(), to.clone()).map_err(|_e| NFTError::NotAuthorized).unwrap();
+        add_card_to_owner(&env, token_id.clone(), to.clone())?;
 
 
         let config: Config = read_config(&env);
-        let mut balance = read_balance(&env);
 
         // matches!(buy_currency, Currency::Terry)
         //     .then(|| {
@@ -229,43 +268,67 @@ impl NFT {
         //         );
         //     });
 
+        let pays_xtar = pricing::pays_xtar(&config, &buy_currency);
         if buy_currency == Currency::Terry {
-            let amount = card_metadata.price_terry;
-            assert!(user.terry >= amount, "Not enough terry to burn");
-            let withdrawable_amount = (config.withdrawable_percentage as i128 * amount) / 100;
-            let haw_ai_amount = amount - withdrawable_amount;
-            burn_terry(&env, user.owner.clone(), amount);
-            balance.admin_terry += withdrawable_amount;
+            if user.terry < quote.price {
+                return Err(NFTError::InsufficientTerry);
+            }
+            let haw_ai_amount = quote.price - quote.admin;
+            burn_terry(&env, user.owner.clone(), quote.price)?;
+            post(
+                &env,
+                Asset::Terry,
+                Account::External,
+                Account::AdminWithdrawable,
+                quote.admin,
+                Action::Mint,
+            );
             crate::pot::management::accumulate_pot_internal(&env, haw_ai_amount, 0, 0, Some(user.owner.clone()), Some(Action::Mint));
-        } else {
+        } else if pays_xtar {
             let token = token::Client::new(&env, &config.xtar_token.clone());
-            let burnable_amount =
-                (config.burnable_percentage as i128 * card_metadata.price_xtar) / 100;
-            let haw_ai_amount = card_metadata.price_xtar - burnable_amount;
-            token.burn(&to.clone(), &burnable_amount);
+            let haw_ai_amount = quote.price - quote.burn;
+            token.burn(&to.clone(), &quote.burn);
             
             // Transfer XTAR to contract instead of external address
             token.transfer(&to.clone(), &env.current_contract_address(), &haw_ai_amount);
-            
-            // Store XTAR in contract vault
-            let mut vault = read_contract_vault(&env);
-            vault.haw_ai_pot_xtar += haw_ai_amount;
-            write_contract_vault(&env, &vault);
-            
-            balance.haw_ai_xtar += haw_ai_amount;
+
             crate::pot::management::accumulate_pot_internal(&env, 0, 0, haw_ai_amount, Some(user.owner.clone()), Some(Action::Mint));
+        } else if let Currency::Token(payment_token) = &buy_currency {
+            token::Client::new(&env, payment_token).transfer(&to, env.current_contract_address(), &quote.price);
+            tokens::accumulate(&env, payment_token, quote.price, Action::Mint);
         };
-        write_balance(&env, &balance);
+
+        curve::record_mint(&env, token_id.0);
 
         // Emit mint event
-        emit_mint(&env, &to);
+        let (terry, xtar) = match buy_currency {
+            Currency::Terry => (-quote.price, 0),
+            _ if pays_xtar => (0, -quote.price),
+            _ => (0, 0),
+        };
+        emit_mint(
+            &env,
+            &to,
+            &CardEvent {
+                terry,
+                xtar,
+                card_power: card_metadata.initial_power,
+                ..card_event(&env, &token_id)
+            },
+        );
+        Ok(())
     }
 
-    pub fn transfer(env: Env, from: Address, to: Address, token_id: TokenId) {
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: TokenId) -> Result<(), NFTError> {
         from.require_auth();
-        let nft: Card = read_nft(&env, from.clone(), token_id.clone()).unwrap();
+        let nft: Card = read_nft(&env, from.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
         // Prevent transferring cards locked by an action
-        assert!(nft.locked_by_action == Action::None, "Card is locked by an action");
+        if nft.locked_by_action != Action::None {
+            return Err(NFTError::CardLocked);
+        }
+        if exists(&env, to.clone(), token_id.clone()) {
+            return Err(NFTError::CardAlreadyOwned);
+        }
         // Update owner-owned card indexes
         let mut from_cards = read_owner_card(&env, from.clone());
         if let Some(pos) = from_cards.iter().position(|x| x == token_id.clone()) {
@@ -273,16 +336,26 @@ impl NFT {
             write_owner_card(&env, from.clone(), from_cards);
         }
         remove_nft(&env, from.clone(), token_id.clone());
+        let card_power = nft.power;
         write_nft(&env, to.clone(), token_id.clone(), nft);
         let mut to_cards = read_owner_card(&env, to.clone());
-        to_cards.push_back(token_id);
+        to_cards.push_back(token_id.clone());
         write_owner_card(&env, to.clone(), to_cards);
 
         // Emit transfer event
-        emit_transfer(&env, &from, &to);
+        emit_transfer(
+            &env,
+            &from,
+            &to,
+            &CardEvent {
+                card_power,
+                ..card_event(&env, &token_id)
+            },
+        );
+        Ok(())
     }
 
-    pub fn burn(env: Env, user: Address, token_id: TokenId) {
+    pub fn burn(env: Env, user: Address, token_id: TokenId) -> Result<(), NFTError> {
         burn::burn(env, user, token_id)
     }
 
@@ -292,6 +365,22 @@ impl NFT {
         e.deployer().update_current_contract_wasm(new_wasm_hash);
     }
 
+    /// One-shot migration for contracts deployed before the ledger: seeds its
+    /// accounts from the old vault and balance records, rewrites the config in
+    /// the current layout, takes the first voting weight checkpoints and links
+    /// the rounds already opened to their neighbours. Run it right after
+    /// `upgrade`; ledger postings and proposals fail with `MigrationPending`
+    /// until then.
+    /// Returns false when there was nothing to migrate.
+    pub fn migrate(e: Env) -> bool {
+        let admin: Address = read_administrator(&e);
+        admin.require_auth();
+        let config_migrated = migrate_config(&e);
+        let weights_seeded = weights::seed(&e);
+        let rounds_linked = link_rounds(&e);
+        ledger::migrate_legacy_balances(&e) || config_migrated || weights_seeded || rounds_linked
+    }
+
     pub fn set_admin(e: Env, new_admin: Address) {
         let admin = read_administrator(&e);
         admin.require_auth();
@@ -299,7 +388,7 @@ impl NFT {
             .instance()
             .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
         write_administrator(&e, &new_admin);
-        TokenUtils::new(&e).events().set_admin(admin, new_admin);
+        emit_admin_changed(&e, &admin, &new_admin);
     }
 
     pub fn check_admin(e: Env) -> bool {
@@ -325,6 +414,7 @@ impl NFT {
             e.storage()
                 .persistent()
                 .set(&DataKey::Whitelist(member.clone()), &true);
+            emit_whitelist_updated(e, &member, true);
         }
     }
 
@@ -335,6 +425,7 @@ impl NFT {
             e.storage()
                 .persistent()
                 .remove(&DataKey::Whitelist(member.clone()));
+            emit_whitelist_updated(e, &member, false);
         }
     }
 
@@ -361,6 +452,7 @@ impl NFT {
     pub fn create_metadata(e: &Env, card: CardMetadata, id: u32) {
         let admin = read_administrator(&e);
         admin.require_auth();
+        emit_metadata_created(e, id, &card);
         write_metadata(e, id, card);
     }
 
@@ -378,6 +470,7 @@ impl NFT {
             total_history_terry: 0,
             level: 1,
         };
+        emit_user_created(&e, &user);
         write_user(&e, address, user);
     }
 
@@ -410,18 +503,18 @@ impl NFT {
         player_cards
     }
 
-    pub fn add_power_to_card(env: &Env, player: Address, token_id: u32, amount: u32) {
-        let card = read_nft(env, player.clone(), TokenId(token_id)).unwrap();
+    pub fn add_power_to_card(env: &Env, player: Address, token_id: u32, amount: u32) -> Result<(), NFTError> {
+        let card = read_nft(env, player.clone(), TokenId(token_id)).ok_or(NFTError::CardNotFound)?;
+        let mut user = read_user(env, player.clone());
+        user.power = user.power.checked_sub(amount).ok_or(NFTError::InsufficientPower)?;
         // Cap power to metadata max
         let metadata = crate::metadata::read_metadata(env, token_id);
         let new_power = (card.power as u128 + amount as u128)
             .min(metadata.max_power as u128) as u32;
         let new_card = Card { power: new_power, locked_by_action: card.locked_by_action };
         write_nft(env, player.clone(), TokenId(token_id), new_card);
-        let mut user = read_user(env, player.clone());
-        assert!(user.power >= amount, "Insufficient user POWER");
-        user.power = user.power.checked_sub(amount).expect("POWER underflow");
         write_user(env, player.clone(), user);
+        Ok(())
     }
 
     pub fn read_user(env: &Env, player: Address) -> User {
@@ -437,30 +530,23 @@ impl NFT {
         (read_pot_balance(&env), read_dogstar_balance(&env))
     }
 
+    /// Projected share of the pot accumulated so far, from the player's power-seconds
+    /// in the running period.
     pub fn get_player_potential_reward(env: Env, player: Address) -> PendingReward {
         let current_round = get_current_round(&env);
         let balance = read_pot_balance(&env);
-        let deck = read_deck(env.clone(), player.clone());
-        let players = get_eligible_players(&env);
-        let mut total_effective_power: u128 = 0;
-        for p in players.iter() {
-            let d = read_deck(env.clone(), p.clone());
-            if d.token_ids.len() == 4 {
-                total_effective_power += calculate_effective_power(d.total_power, d.bonus) as u128;
-            }
-        }
-        const PRECISION: u128 = 10000;
-        let effective_power = calculate_effective_power(deck.total_power, deck.bonus) as u128;
-        let share = if total_effective_power > 0 {
-            ((effective_power * PRECISION) / total_effective_power) as u128
+        let total_power_time = read_power_time(&env);
+        const PRECISION: i128 = 10000;
+        let share = if total_power_time > 0 {
+            read_player_power_time(&env, &player, current_round) * PRECISION / total_power_time
         } else {
             0
         };
         PendingReward {
             round_number: current_round,
-            terry_amount: (balance.accumulated_terry * share as i128) / PRECISION as i128,
-            power_amount: (balance.accumulated_power as u128 * share / PRECISION) as u32,
-            xtar_amount: (balance.accumulated_xtar * share as i128) / PRECISION as i128,
+            terry_amount: (balance.accumulated_terry * share) / PRECISION,
+            power_amount: (balance.accumulated_power as i128 * share / PRECISION) as u32,
+            xtar_amount: (balance.accumulated_xtar * share) / PRECISION,
             status: RewardStatus::Pending,
         }
     }
@@ -469,297 +555,441 @@ impl NFT {
         read_pot_snapshot(&e, round)
     }
 
+    /// Shares are no longer stored per round. Without a stored record the share is
+    /// derived from the player's power-seconds in the period the round paid out,
+    /// which is known for the player's last two periods.
     pub fn get_player_participation(env: Env, player: Address, round: u32) -> Option<PlayerReward> {
-        read_player_reward(&env, round, &player)
+        if let Some(reward) = read_player_reward(&env, round, &player) {
+            return Some(reward);
+        }
+        let snapshot = read_pot_snapshot(&env, round)?;
+        if snapshot.mode != DistributionMode::Proportional {
+            return None;
+        }
+        let power_time = read_player_power_time(&env, &player, previous_round(&env, round));
+        if power_time == 0 || snapshot.time_weighted_power == 0 {
+            return None;
+        }
+        let deck = read_deck(env.clone(), player.clone());
+        Some(PlayerReward {
+            share_percentage: (power_time * 10000 / snapshot.time_weighted_power) as u32,
+            effective_power: read_effective_power(&env, &player),
+            round_number: round,
+            deck_bonus: deck.bonus,
+            deck_categories: deck.deck_categories,
+            rank: 0,
+        })
     }
 
+    /// Unclaimed pot rewards by the round that paid them.
     pub fn get_pending_rewards(env: Env, player: Address) -> Vec<PendingReward> {
-        let mut pending = Vec::new(&env);
-        for round in get_all_rounds(&env).iter() {
-            if let Some(reward) = read_pending_reward(&env, round, &player) {
-                if reward.status != RewardStatus::Claimed {
-                    pending.push_back(reward);
-                }
-            }
-        }
-        pending
+        reward::pending_rewards(&env, &read_claimable(&env, &player))
     }
 
     pub fn accumulate_pot(env: Env, terry: i128, power: u32, xtar: i128, from: Option<Address>, action: Option<Action>) {
         let admin = read_administrator(&env);
         admin.require_auth();
-        let config = read_config(&env);
-        let mut pot_balance = read_pot_balance(&env);
-        let mut vault = read_contract_vault(&env);
-        
-        let fee_percentage = config.dogstar_fee_percentage;
-        let terry_fee = (terry * fee_percentage as i128) / 10000;
-        let power_fee = (power * fee_percentage) / 10000;
-        let xtar_fee = (xtar * fee_percentage as i128) / 10000;
-        
-        // Accumulate in pot balance (minus dogstar fees)
-        pot_balance.accumulated_terry += terry - terry_fee;
-        pot_balance.accumulated_power += power - power_fee;
-        pot_balance.accumulated_xtar += xtar - xtar_fee;
-        pot_balance.last_updated = env.ledger().timestamp();
-        
-        // Store dogstar fees in vault instead of separate balance
-        vault.dogstar_terry += terry_fee;
-        vault.dogstar_power += power_fee;
-        vault.dogstar_xtar += xtar_fee;
-        
-        write_pot_balance(&env, &pot_balance);
-        write_contract_vault(&env, &vault);
-        
-        // Keep old balance for backward compatibility (can be removed later)
-        let mut dogstar_balance = read_dogstar_balance(&env);
-        dogstar_balance.terry += terry_fee;
-        dogstar_balance.power += power_fee;
-        dogstar_balance.xtar += xtar_fee;
-        write_dogstar_balance(&env, &dogstar_balance);
-        
-        if terry_fee > 0 || power_fee > 0 || xtar_fee > 0 {
-            emit_dogstar_fee_accumulated(&env, terry_fee, power_fee, xtar_fee, fee_percentage, from, action);
-        }
-    }
-
-    pub fn claim_dogstar_fees(env: Env, claimer: Address) {
-        // Restrict to admin only for protocol fee claims
-        let admin = read_administrator(&env);
-        admin.require_auth();
-        assert!(claimer == admin, "Only admin can claim dogstar fees");
-        
-        // Read the claimable balance for dogstar
-        let mut claimable = read_dogstar_claimable(&env);
-        let config = read_config(&env);
-        
-        // Check if there are fees to claim
-        if claimable.terry == 0 && claimable.power == 0 && claimable.xtar == 0 {
-            panic!("No fees available to claim");
-        }
-        
-        let terry_to_claim = claimable.terry;
-        let power_to_claim = claimable.power;
-        let xtar_to_claim = claimable.xtar;
-        
-        // Transfer assets to claimer
-        if terry_to_claim > 0 {
-            let mut user = read_user(&env, claimer.clone());
-            user.terry += terry_to_claim;
-            write_user(&env, claimer.clone(), user);
-            claimable.terry = 0;
-        }
-        
-        if power_to_claim > 0 {
-            let mut user = read_user(&env, claimer.clone());
-            user.power += power_to_claim;
-            write_user(&env, claimer.clone(), user);
-            claimable.power = 0;
-        }
-        
-        if xtar_to_claim > 0 {
-            let token = token::Client::new(&env, &config.xtar_token);
-            token.transfer(&env.current_contract_address(), &claimer, &xtar_to_claim);
-            claimable.xtar = 0;
-        }
-        
-        // Update claim record
-        claimable.last_claim_timestamp = env.ledger().timestamp();
-        claimable.last_claim_round = get_current_round(&env);
-        write_dogstar_claimable(&env, &claimable);
-        
-        // Update vault to reflect claimed amounts
-        let mut vault = read_contract_vault(&env);
-        vault.dogstar_terry -= terry_to_claim;
-        vault.dogstar_power -= power_to_claim;
-        vault.dogstar_xtar -= xtar_to_claim;
-        write_contract_vault(&env, &vault);
-        
-        emit_dogstar_fee_withdrawn(&env, &claimer, terry_to_claim, power_to_claim, xtar_to_claim);
+        accumulate_pot_internal(&env, terry, power, xtar, from, action);
     }
-    
-    // Admin function to make dogstar fees claimable
+
+    // Admin function to split released dogstar fees among the fee recipients
     pub fn release_dogstar_fees(env: Env) {
         let admin = read_administrator(&env);
         admin.require_auth();
-        
-        let vault = read_contract_vault(&env);
-        let mut claimable = read_dogstar_claimable(&env);
-        
-        // Move fees from vault to claimable
-        claimable.terry += vault.dogstar_terry;
-        claimable.power += vault.dogstar_power;
-        claimable.xtar += vault.dogstar_xtar;
-        
-        write_dogstar_claimable(&env, &claimable);
-        
-        // Note: We don't zero out vault.dogstar_* here to keep track of total accumulated
-        // The claim function will handle the actual deduction
+        fees::release(&env);
+    }
+
+    /// Reconciles XTAR liabilities against the contract's token balance.
+    pub fn audit(env: Env) -> SolvencyReport {
+        audit::audit(&env)
+    }
+
+    pub fn is_solvent(env: Env) -> bool {
+        audit::audit(&env).solvent
+    }
+
+    /// Reconciles the liabilities in a registered token against the contract's balance.
+    pub fn audit_token(env: Env, token: Address) -> TokenSolvencyReport {
+        audit::audit_token(&env, &token)
     }
 
     pub fn open_pot(env: Env, round: u32) -> Result<(), NFTError> {
         let admin = read_administrator(&env);
         admin.require_auth();
+        ensure_no_opening(&env);
         let current_round = get_current_round(&env);
         if round <= current_round {
             return Err(NFTError::RoundAlreadyProcessed);
         }
-        let balance = read_pot_balance(&env);
-        let mut vault = read_contract_vault(&env);
-        
-        // Move pot balance to vault for distribution
-        vault.haw_ai_pot_terry += balance.accumulated_terry;
-        vault.haw_ai_pot_power += balance.accumulated_power;
-        vault.haw_ai_pot_xtar += balance.accumulated_xtar;
-        vault.total_claimable_terry += balance.accumulated_terry;
-        vault.total_claimable_power += balance.accumulated_power;
-        vault.total_claimable_xtar += balance.accumulated_xtar;
-        write_contract_vault(&env, &vault);
-        
+        Self::open_round(&env, round);
+        if read_distribution_mode(&env, round) != DistributionMode::Proportional {
+            opening::run(&env, round);
+        }
+        Ok(())
+    }
+
+    /// First phase of a pot opening spread over several transactions: takes the
+    /// snapshot, distributes a proportional pot or holds a tiered or lottery one
+    /// for its winners, and freezes the participant cursor. Claims and further
+    /// openings are blocked until `finalize_open_pot`.
+    pub fn begin_open_pot(env: Env, round: u32) -> Result<OpeningProgress, NFTError> {
+        let admin = read_administrator(&env);
+        admin.require_auth();
+        ensure_no_opening(&env);
+        if round <= get_current_round(&env) {
+            return Err(NFTError::RoundAlreadyProcessed);
+        }
+        Self::open_round(&env, round);
+        Ok(opening::begin(&env, round))
+    }
+
+    /// Settles up to `batch_size` more participants of the round being opened and
+    /// records their participation or weighs them for its winners. Permissionless,
+    /// so keeper-opened rounds can be completed by anyone.
+    pub fn continue_open_pot(env: Env, round: u32, batch_size: u32) -> Result<OpeningProgress, EconomyError> {
+        opening::advance(&env, round, batch_size)
+    }
+
+    /// Completes a batched opening once every participant is processed and pays
+    /// the winners of a tiered or lottery round. Permissionless.
+    pub fn finalize_open_pot(env: Env, round: u32) -> Result<OpeningProgress, EconomyError> {
+        opening::finalize(&env, round)
+    }
+
+    /// Sets how the opening of `round` pays out: proportionally, to top-N tiers or
+    /// to lottery winners.
+    pub fn set_distribution_mode(env: Env, round: u32, mode: DistributionMode) -> Result<(), NFTError> {
+        modes::set_distribution_mode(env, round, mode)
+    }
+
+    pub fn distribution_mode(env: Env, round: u32) -> DistributionMode {
+        read_distribution_mode(&env, round)
+    }
+
+    /// Progress of a batched opening; `None` for rounds opened in one call.
+    pub fn open_pot_progress(env: Env, round: u32) -> Option<OpeningProgress> {
+        opening::read_opening(&env, round)
+    }
+
+    /// Opens the pot as the next round once `round_duration` has passed since the
+    /// last opening, or since rounds were first configured for the first round.
+    /// Permissionless; the keeper is credited `keeper_reward_bps` of the pot as a
+    /// claimable balance before the rest is distributed. Tiered and lottery rounds
+    /// begin a batched opening that anyone completes with `continue_open_pot` and
+    /// `finalize_open_pot`.
+    pub fn advance_round(env: Env, keeper: Address) -> Result<u32, EconomyError> {
+        keeper.require_auth();
+        ensure_no_opening(&env);
+
+        let config = read_config(&env);
+        if config.round_duration == 0 {
+            return Err(EconomyError::RoundDurationNotSet);
+        }
+        let current_round = get_current_round(&env);
+        let started = read_pot_snapshot(&env, current_round)
+            .map(|s| s.timestamp)
+            .or_else(|| read_rounds_started_at(&env))
+            .unwrap_or(0);
+        if env.ledger().timestamp() < started.saturating_add(config.round_duration) {
+            return Err(EconomyError::RoundNotOver);
+        }
+
+        let round = current_round + 1;
+        let (terry, power, xtar) = pay_keeper_reward(&env, &keeper, config.keeper_reward_bps);
+        Self::open_round(&env, round);
+        if read_distribution_mode(&env, round) != DistributionMode::Proportional {
+            opening::begin(&env, round);
+        }
+        emit_round_advanced(&env, &keeper, round, terry, power, xtar);
+        Ok(round)
+    }
+
+    fn open_round(env: &Env, round: u32) {
+        let balance = read_pot_balance(env);
+        let previous = get_current_round(env);
+
+        // Journal entries from here on belong to the new round
+        set_current_round(env, round);
+        add_round(env, round);
+
+        // Set the reserve and rollover aside, and release the jackpot when it is due
+        let split = reserve::split(
+            env,
+            &read_config(env),
+            round,
+            balance.total_openings + 1,
+            &PotAmounts {
+                terry: balance.accumulated_terry,
+                power: balance.accumulated_power,
+                xtar: balance.accumulated_xtar,
+            },
+        );
+        let pot_before = ledger::read_account(env, Account::Pot);
+        let payout = &split.distributable;
+
+        // Proportional rounds pay the pot out over the power-seconds of the closing
+        // period by raising the reward indexes; players accrue when they claim or
+        // change decks. Other modes hold the payout until the opening has weighed
+        // every deck for winners.
+        let mode = read_distribution_mode(env, round);
+        let proportional = mode == DistributionMode::Proportional;
+        let closed = if proportional {
+            close_period(env, previous, payout.terry, payout.power, payout.xtar)
+        } else {
+            close_period(env, previous, 0, 0, 0)
+        };
+        tokens::distribute(env, round, previous, &closed, proportional);
+        if !proportional {
+            modes::hold(env, round, payout);
+        }
+        let pot_after = ledger::read_account(env, Account::Pot);
+
+        let index = read_reward_index(env);
         let snapshot = PotSnapshot {
             round_number: round,
             total_terry: balance.accumulated_terry,
             total_power: balance.accumulated_power,
             total_xtar: balance.accumulated_xtar,
             timestamp: env.ledger().timestamp(),
-            total_participants: 0,
-            total_effective_power: 0,
+            total_participants: index.participants,
+            total_effective_power: index.total_effective_power,
+            time_weighted_power: closed.power_time,
+            distributed: PotAmounts {
+                terry: pot_before.terry - pot_after.terry,
+                power: (pot_before.power - pot_after.power) as u32,
+                xtar: pot_before.xtar - pot_after.xtar,
+            },
+            rolled_over: split.rolled_over.clone(),
+            reserved: split.reserved,
+            jackpot: split.jackpot,
+            mode,
+            winners: Vec::new(env),
         };
-        write_pot_snapshot(&env, round, &snapshot);
-        emit_pot_opened(&env, round, &snapshot);
-        
-        // Calculate and store player shares as claimable balances
-        Self::calculate_and_store_claimable_shares(&env, round, &snapshot);
-        
-        set_current_round(&env, round);
-        add_round(&env, round);
+        write_pot_snapshot(env, round, &snapshot);
+        emit_pot_opened(env, round, &snapshot);
+
         write_pot_balance(
-            &env,
+            env,
             &PotBalance {
-                accumulated_terry: 0,
-                accumulated_power: 0,
-                accumulated_xtar: 0,
+                accumulated_terry: split.rolled_over.terry,
+                accumulated_power: split.rolled_over.power,
+                accumulated_xtar: split.rolled_over.xtar,
                 last_opening_round: round,
                 total_openings: balance.total_openings + 1,
                 last_updated: env.ledger().timestamp(),
             },
         );
-
-        Ok(())
     }
     
-    fn calculate_and_store_claimable_shares(env: &Env, round: u32, snapshot: &PotSnapshot) {
-        calculate_player_shares(env, round);
-        
-        // Get all participants for this round
-        let decks = read_decks(env.clone());
-        
-        for deck in decks.iter() {
-            if let Some(player_reward) = read_player_reward(env, round, &deck.owner) {
-                // Calculate share based on effective power
-                let share_percentage = player_reward.share_percentage;
-                
-                let terry_share = (snapshot.total_terry * share_percentage as i128) / 10000;
-                let power_share = (snapshot.total_power * share_percentage) / 10000;
-                let xtar_share = (snapshot.total_xtar * share_percentage as i128) / 10000;
-                
-                // Update user's claimable balance
-                let mut user_claimable = read_user_claimable_balance(env, &deck.owner);
-                user_claimable.terry += terry_share;
-                user_claimable.power += power_share;
-                user_claimable.xtar += xtar_share;
-                user_claimable.last_claim_round = round;
-                write_user_claimable_balance(env, &deck.owner, &user_claimable);
-            }
+    /// Claims every pot reward now. Rewards still vesting are paid less
+    /// `early_claim_penalty_bps`, which goes back into the pot.
+    pub fn claim_haw_ai_pot_share(env: Env, player: Address) -> Result<(i128, u32, i128), NFTError> {
+        player.require_auth();
+        ensure_no_opening(&env);
+
+        accrue(&env, &player);
+        let mut claimable = read_user_claimable_balance(&env, &player);
+        let config = read_config(&env);
+
+        if claimable.terry == 0 && claimable.power == 0 && claimable.xtar == 0 {
+            return Err(NFTError::NoRewardsAvailable);
         }
+
+        // Early claim penalty on each round's locked part, recycled into the next pot
+        let now = env.ledger().timestamp();
+        let bps = config.early_claim_penalty_bps as i128;
+        let mut penalty = PotAmounts { terry: 0, power: 0, xtar: 0 };
+        let mut parts = Vec::new(&env);
+        for entry in claimable.rounds.iter() {
+            let unlocked = vesting::unlocked(&entry, now);
+            let forfeited = PotAmounts {
+                terry: (entry.terry - unlocked.terry) * bps / 10000,
+                power: ((entry.power - unlocked.power) as i128 * bps / 10000) as u32,
+                xtar: (entry.xtar - unlocked.xtar) * bps / 10000,
+            };
+            penalty.terry += forfeited.terry;
+            penalty.power += forfeited.power;
+            penalty.xtar += forfeited.xtar;
+            parts.push_back(ClaimableRound {
+                terry: entry.terry - forfeited.terry,
+                power: entry.power - forfeited.power,
+                xtar: entry.xtar - forfeited.xtar,
+                ..entry
+            });
+        }
+        if penalty.terry > 0 || penalty.power > 0 || penalty.xtar > 0 {
+            post_all(
+                &env,
+                Account::Claimable,
+                Account::Pot,
+                penalty.terry,
+                penalty.power,
+                penalty.xtar,
+                Action::None,
+            );
+            emit_early_claim_penalty(&env, &player, &penalty);
+        }
+
+        let claimed = PotAmounts {
+            terry: claimable.terry - penalty.terry,
+            power: claimable.power - penalty.power,
+            xtar: claimable.xtar - penalty.xtar,
+        };
+        claimable.terry = 0;
+        claimable.power = 0;
+        claimable.xtar = 0;
+        claimable.rounds = Vec::new(&env);
+        let paid = Self::pay_pot_share(&env, &player, &config, &mut claimable, &claimed, &parts);
+        Ok((paid.terry, paid.power, paid.xtar))
     }
 
-    pub fn claim_haw_ai_pot_share(env: Env, player: Address) -> Result<(i128, u32, i128), NFTError> {
+    /// Claims the pot rewards that have vested, without penalty.
+    pub fn claim_vested_pot_share(env: Env, player: Address) -> Result<(i128, u32, i128), NFTError> {
         player.require_auth();
+        ensure_no_opening(&env);
 
+        accrue(&env, &player);
         let mut claimable = read_user_claimable_balance(&env, &player);
         let config = read_config(&env);
 
-        if claimable.terry == 0 && claimable.power == 0 && claimable.xtar == 0 {
+        let (vested, parts) = vesting::take_vested(&env, &mut claimable);
+        if vested.terry == 0 && vested.power == 0 && vested.xtar == 0 {
             return Err(NFTError::NoRewardsAvailable);
         }
-        
-        let terry_to_claim = claimable.terry;
-        let power_to_claim = claimable.power;
-        let xtar_to_claim = claimable.xtar;
-        
+        let paid = Self::pay_pot_share(&env, &player, &config, &mut claimable, &vested, &parts);
+        Ok((paid.terry, paid.power, paid.xtar))
+    }
+
+    /// Pays `amounts`, which `parts` break down by round, out of the claimable
+    /// account to `player` and records the claim; `claimable` must already exclude
+    /// them. XTAR the player cannot receive stays claimable. Returns what was paid.
+    fn pay_pot_share(
+        env: &Env,
+        player: &Address,
+        config: &Config,
+        claimable: &mut UserClaimableBalance,
+        amounts: &PotAmounts,
+        parts: &Vec<ClaimableRound>,
+    ) -> PotAmounts {
+        let mut rewards = reward::by_round(env, parts, amounts, RewardStatus::Claimed);
+        let mut paid = amounts.clone();
+
         // Transfer assets to player
-        if terry_to_claim > 0 {
-            mint_terry(&env, player.clone(), terry_to_claim);
-            claimable.terry = 0;
-        }
-        
-        if power_to_claim > 0 {
-            let mut user = read_user(&env, player.clone());
-            user.power += power_to_claim;
-            write_user(&env, player.clone(), user);
-            claimable.power = 0;
-        }
-        
-        if xtar_to_claim > 0 {
-            let token = token::Client::new(&env, &config.xtar_token);
-            token.transfer(&env.current_contract_address(), &player, &xtar_to_claim);
-            claimable.xtar = 0;
-        }
-        
+        if amounts.terry > 0 {
+            mint_terry(env, player.clone(), amounts.terry);
+        }
+
+        if amounts.power > 0 {
+            let remainder = compound::compound(env, player, amounts.power);
+            let mut user = read_user(env, player.clone());
+            user.power += remainder;
+            write_user(env, player.clone(), user);
+        }
+
+        if amounts.xtar > 0 {
+            let token = token::Client::new(env, &config.xtar_token);
+            if token
+                .try_transfer(&env.current_contract_address(), player, &amounts.xtar)
+                .is_err()
+            {
+                // Most likely a missing trust line; the XTAR waits for the next claim
+                paid.xtar = 0;
+                for (i, mut reward) in rewards.clone().iter().enumerate() {
+                    if reward.xtar_amount == 0 {
+                        continue;
+                    }
+                    let xtar = PotAmounts { terry: 0, power: 0, xtar: reward.xtar_amount };
+                    expiry::add(env, claimable, reward.round_number, &xtar);
+                    reward.status = RewardStatus::AwaitingTrustLine;
+                    emit_reward_pending(env, player, &reward);
+                    rewards.set(i as u32, reward);
+                }
+            }
+        }
+
         // Update claim record
         claimable.last_claim_timestamp = env.ledger().timestamp();
-        write_user_claimable_balance(&env, &player, &claimable);
-        
-        // Update vault to reflect claimed amounts
-        let mut vault = read_contract_vault(&env);
-        vault.total_claimable_terry -= terry_to_claim;
-        vault.total_claimable_power -= power_to_claim;
-        vault.total_claimable_xtar -= xtar_to_claim;
-        write_contract_vault(&env, &vault);
-        
+        write_user_claimable_balance(env, player, claimable);
+        reward::record_claim(env, player, rewards);
+
+        post_all(
+            env,
+            Account::Claimable,
+            Account::External,
+            paid.terry,
+            paid.power,
+            paid.xtar,
+            Action::None,
+        );
+
         // Emit event
-        emit_rewards_claimed(&env, &player, terry_to_claim, power_to_claim, xtar_to_claim);
+        emit_rewards_claimed(env, player, paid.terry, paid.power, paid.xtar);
+        paid
+    }
 
-        Ok((terry_to_claim, power_to_claim, xtar_to_claim))
+    /// Up to `limit` of the player's pot reward claims and sweeps from id `start`
+    /// on, oldest first, each broken down by round.
+    pub fn claim_history(env: Env, player: Address, start: u32, limit: u32) -> Vec<RewardClaim> {
+        reward::read_claim_history(&env, &player, start, limit)
     }
-    
+
+    /// Sets where the player's claimed pot POWER goes: `User.power`, its deck
+    /// cards or an active stake.
+    pub fn set_auto_compound(env: Env, player: Address, preference: AutoCompound) -> Result<(), NFTError> {
+        player.require_auth();
+        compound::set_auto_compound(&env, &player, &preference)
+    }
+
+    pub fn auto_compound(env: Env, player: Address) -> AutoCompound {
+        compound::read_auto_compound(&env, &player)
+    }
+
+    /// Vested and locked parts of the player's claimable pot rewards.
+    pub fn view_vesting(env: Env, player: Address) -> VestingBalance {
+        vesting::read_vesting(&env, &read_claimable(&env, &player))
+    }
+
+    /// Claimable balance, including rewards accrued since the player last settled,
+    /// with the amount each round paid and when it expires.
     pub fn view_claimable_balance(env: Env, player: Address) -> UserClaimableBalance {
-        read_user_claimable_balance(&env, &player)
+        read_claimable(&env, &player)
+    }
+
+    /// Returns the player's pot rewards older than `claim_expiry_rounds` to the
+    /// pot. Permissionless. Returns the (TERRY, POWER, XTAR) swept.
+    pub fn sweep_expired_claimables(env: Env, player: Address) -> Result<(i128, u32, i128), NFTError> {
+        ensure_no_opening(&env);
+        accrue(&env, &player);
+        let swept = expiry::sweep(&env, &player);
+        Ok((swept.terry, swept.power, swept.xtar))
     }
     
     pub fn view_vault_status(env: Env) -> ContractVault {
         read_contract_vault(&env)
     }
     
-    pub fn claim_all_pending_rewards(env: Env, player: Address) -> Result<(i128, u32, i128), NFTError> {
-        // Legacy function - redirect to new claim function
-        Self::claim_haw_ai_pot_share(env, player)
-    }
-
-    pub fn update_dogstar_fee_percentage(env: Env, fee_percentage: u32) {
+    pub fn update_dogstar_fee_percentage(env: Env, fee_percentage: u32) -> Result<(), NFTError> {
         let admin = read_administrator(&env);
         admin.require_auth();
-        
-        // Maximum fee percentage (50% = 5000 basis points)
-        const MAX_FEE_PERCENTAGE: u32 = 5000;
-        assert!(fee_percentage <= MAX_FEE_PERCENTAGE, "Fee percentage exceeds maximum (50%)");
 
         let mut config = read_config(&env);
+        ensure_no_timelock(&config)?;
         let old_fee = config.dogstar_fee_percentage;
         config.dogstar_fee_percentage = fee_percentage;
+        validate_config(&config)?;
         write_config(&env, &config);
         emit_dogstar_fee_percentage_updated(&env, old_fee, fee_percentage);
+        Ok(())
     }
 
-    pub fn contribute_to_pot(env: Env, terry: i128, power: u32, xtar: i128) {
+    pub fn contribute_to_pot(env: Env, terry: i128, power: u32, xtar: i128) -> Result<(), NFTError> {
         let admin = read_administrator(&env);
         admin.require_auth();
-        assert!(
-            terry >= 0 && xtar >= 0,
-            "Negative contributions not allowed"
-        );
-        Self::accumulate_pot(env, terry, power, xtar, None, None);
+        if terry < 0 || xtar < 0 {
+            return Err(NFTError::NegativeAmount);
+        }
+        accumulate_pot_internal(&env, terry, power, xtar, None, None);
+        Ok(())
     }
 
     pub fn get_eligible_players(env: Env) -> Vec<Address> {
@@ -777,6 +1007,250 @@ impl NFT {
     pub fn get_current_round(env: Env) -> u32 {
         get_current_round(&env)
     }
+
+    /// Cumulative pot rewards per unit of effective power and the power earning them.
+    pub fn reward_index(env: Env) -> RewardIndex {
+        read_reward_index(&env)
+    }
+
+    pub fn register_pot_token(env: Env, token: Address) -> Result<(), EconomyError> {
+        tokens::register_pot_token(env, token)
+    }
+
+    pub fn pot_tokens(env: Env) -> Vec<PotTokenBalance> {
+        tokens::read_pot_token_balances(&env)
+    }
+
+    pub fn deposit_pot_token(env: Env, from: Address, token: Address, amount: i128) -> Result<(), EconomyError> {
+        tokens::deposit(env, from, token, amount)
+    }
+
+    pub fn pot_token_snapshot(env: Env, round: u32, token: Address) -> i128 {
+        tokens::read_pot_token_snapshot(&env, round, &token)
+    }
+
+    pub fn pot_token_claimable(env: Env, player: Address, token: Address) -> i128 {
+        tokens::read_pot_token_claimable(&env, &player, &token)
+    }
+
+    pub fn claim_pot_token(env: Env, player: Address, token: Address) -> Result<i128, NFTError> {
+        tokens::claim(env, player, token)
+    }
+}
+
+// Ledger
+#[contractimpl]
+impl NFT {
+    pub fn ledger_account(env: Env, account: Account) -> AccountBalance {
+        ledger::read_account(&env, account)
+    }
+
+    pub fn ledger_accounts(env: Env) -> Vec<AccountBalance> {
+        ledger::read_accounts(&env)
+    }
+
+    /// Up to `limit` (at most 50) journal entries filed under `round`, skipping
+    /// the first `start`.
+    pub fn journal(env: Env, round: u32, start: u32, limit: u32) -> Vec<JournalEntry> {
+        ledger::read_journal(&env, round, start, limit)
+    }
+
+    /// Ids of the journal entries filed under `round`.
+    pub fn journal_range(env: Env, round: u32) -> Option<JournalRange> {
+        ledger::read_journal_range(&env, round)
+    }
+}
+
+// Treasury
+#[contractimpl]
+impl NFT {
+    pub fn set_treasurer(env: Env, treasurer: Option<Address>) {
+        treasury::set_treasurer(env, treasurer)
+    }
+
+    pub fn treasurer(env: Env) -> Option<Address> {
+        treasury::read_treasurer(&env)
+    }
+
+    pub fn withdraw_admin_balance(
+        env: Env,
+        caller: Address,
+        to: Address,
+        asset: Asset,
+        amount: i128,
+    ) -> Result<(), NFTError> {
+        treasury::withdraw_admin(env, caller, to, asset, amount)
+    }
+
+    pub fn sweep_haw_ai(env: Env, caller: Address, asset: Asset, amount: i128) -> Result<(), NFTError> {
+        treasury::sweep_haw_ai(env, caller, asset, amount)
+    }
+
+    /// Up to `limit` (at most 50) treasury records from id `start` on.
+    pub fn treasury_history(env: Env, start: u32, limit: u32) -> Vec<TreasuryRecord> {
+        treasury::read_treasury_history(&env, start, limit)
+    }
+}
+
+// Pricing
+#[contractimpl]
+impl NFT {
+    pub fn set_usd_price(env: Env, token_id: u32, price_usd: Option<i128>) -> Result<(), NFTError> {
+        pricing::set_usd_price(env, token_id, price_usd)
+    }
+
+    pub fn usd_price(env: Env, token_id: u32) -> Option<i128> {
+        pricing::read_usd_price(&env, token_id)
+    }
+
+    pub fn register_payment_token(env: Env, token: Address, oracle_asset: fight::Asset) {
+        pricing::register_payment_token(env, token, oracle_asset)
+    }
+
+    pub fn payment_tokens(env: Env) -> Vec<PaymentToken> {
+        pricing::read_payment_tokens(&env)
+    }
+
+    pub fn mint_quote(env: Env, token_id: u32, currency: Currency) -> Result<MintQuote, NFTError> {
+        pricing::mint_quote(env, token_id, currency)
+    }
+
+    pub fn set_price_curve(env: Env, token_id: u32, curve: Option<PriceCurve>) -> Result<(), NFTError> {
+        curve::set_curve(env, token_id, curve)
+    }
+
+    pub fn price_curve(env: Env, token_id: u32) -> Option<PriceCurve> {
+        curve::read_curve(&env, token_id)
+    }
+
+    pub fn template_price(env: Env, token_id: u32, currency: Currency) -> Result<TemplatePrice, NFTError> {
+        pricing::template_price(env, token_id, currency)
+    }
+}
+
+// Card sales
+#[contractimpl]
+impl NFT {
+    pub fn set_sale(env: Env, token_id: u32, sale: Option<SaleConfig>) -> Result<(), NFTError> {
+        sale::set_sale(env, token_id, sale)
+    }
+
+    pub fn sale(env: Env, token_id: u32) -> Option<SaleConfig> {
+        sale::read_sale(&env, token_id)
+    }
+
+    pub fn sale_phase(env: Env, token_id: u32) -> SalePhase {
+        sale::phase(&env, token_id)
+    }
+
+    pub fn sale_phases(env: Env) -> Vec<TemplatePhase> {
+        sale::read_phases(&env)
+    }
+
+    pub fn sale_minted(env: Env, token_id: u32, wallet: Address) -> u32 {
+        sale::read_minted(&env, token_id, &wallet)
+    }
+}
+
+// Fees
+#[contractimpl]
+impl NFT {
+    pub fn set_fee_recipients(env: Env, recipients: Vec<FeeRecipient>) -> Result<(), NFTError> {
+        fees::set_fee_recipients(env, recipients)
+    }
+
+    pub fn fee_recipients(env: Env) -> Vec<FeeRecipient> {
+        fees::read_fee_recipients(&env)
+    }
+
+    pub fn fee_claimable(env: Env, recipient: Address) -> UserClaimableBalance {
+        fees::read_fee_claimable(&env, &recipient)
+    }
+
+    pub fn claim_fee_share(env: Env, recipient: Address) -> Result<(i128, u32, i128), NFTError> {
+        fees::claim(env, recipient)
+    }
+
+    /// Released fee share of `recipient` in a registered token, e.g. from token mints.
+    pub fn fee_token_claimable(env: Env, recipient: Address, token: Address) -> i128 {
+        fees::read_fee_token_claimable(&env, &recipient, &token)
+    }
+
+    pub fn claim_fee_token_share(env: Env, recipient: Address, token: Address) -> Result<i128, NFTError> {
+        fees::claim_token(env, recipient, token)
+    }
+
+    pub fn set_action_fees(env: Env, action: Action, fees: Option<ActionFees>) -> Result<(), NFTError> {
+        fees::set_action_fees(env, action, fees)
+    }
+
+    pub fn fee_schedule(env: Env) -> Vec<FeeScheduleEntry> {
+        fees::read_fee_schedule(&env)
+    }
+}
+
+// Timelocked parameter changes
+#[contractimpl]
+impl NFT {
+    pub fn schedule_change(env: Env, change: ConfigChange, eta: u64) -> Result<u32, NFTError> {
+        timelock::schedule_change(env, change, eta)
+    }
+
+    pub fn execute_change(env: Env, id: u32) -> Result<(), NFTError> {
+        timelock::execute_change(env, id)
+    }
+
+    pub fn cancel_change(env: Env, id: u32) -> Result<(), NFTError> {
+        timelock::cancel_change(env, id)
+    }
+
+    pub fn pending_changes(env: Env) -> Vec<QueuedChange> {
+        timelock::read_pending_changes(&env)
+    }
+}
+
+// Player governance
+#[contractimpl]
+impl NFT {
+    pub fn set_governance_settings(env: Env, settings: GovernanceSettings) -> Result<(), NFTError> {
+        proposal::set_governance_settings(env, settings)
+    }
+
+    pub fn governance_settings(env: Env) -> GovernanceSettings {
+        proposal::read_governance_settings(&env)
+    }
+
+    pub fn set_proposer(env: Env, member: Address, approved: bool) {
+        proposal::set_proposer(env, member, approved)
+    }
+
+    pub fn create_proposal(env: Env, proposer: Address, change: ConfigChange) -> Result<u32, NFTError> {
+        proposal::create_proposal(env, proposer, change)
+    }
+
+    pub fn vote(env: Env, voter: Address, id: u32, support: bool) -> Result<(), NFTError> {
+        proposal::vote(env, voter, id, support)
+    }
+
+    pub fn finalize_proposal(env: Env, id: u32) -> Result<ProposalStatus, NFTError> {
+        proposal::finalize_proposal(env, id)
+    }
+
+    pub fn proposal(env: Env, id: u32) -> Option<Proposal> {
+        proposal::read_proposal(&env, id)
+    }
+
+    pub fn proposals(env: Env) -> Vec<Proposal> {
+        proposal::read_proposals(&env)
+    }
+
+    pub fn voting_power(env: Env, id: u32, voter: Address) -> u64 {
+        proposal::read_vote_weight(&env, id, &voter)
+    }
+
+    pub fn vote_of(env: Env, id: u32, voter: Address) -> Option<Vote> {
+        proposal::read_vote(&env, id, &voter)
+    }
 }
 
 // Stake, Fight, Lend & Borrow, Deck sections unchanged
@@ -788,7 +1262,7 @@ impl NFT {
         category: Category,
         token_id: TokenId,
         period_index: u32,
-    ) {
+    ) -> Result<(), NFTError> {
         stake::stake(env, user, category, token_id, period_index)
     }
 
@@ -798,11 +1272,11 @@ impl NFT {
         category: Category,
         token_id: TokenId,
         increase_power: u32,
-    ) {
+    ) -> Result<(), NFTError> {
         stake::increase_stake_power(env, user, category, token_id, increase_power)
     }
 
-    pub fn unstake(env: Env, user: Address, category: Category, token_id: TokenId) {
+    pub fn unstake(env: Env, user: Address, category: Category, token_id: TokenId) -> Result<(), NFTError> {
         stake::unstake(env, user, category, token_id)
     }
 
@@ -811,7 +1285,7 @@ impl NFT {
         user: Address,
         category: Category,
         token_id: TokenId,
-    ) -> stake::Stake {
+    ) -> Result<stake::Stake, NFTError> {
         stake::read_stake(env, user, category, token_id)
     }
 
@@ -831,7 +1305,7 @@ impl NFT {
         side_position: SidePosition,
         leverage: u32,
         power_staked: u32,
-    ) {
+    ) -> Result<(), NFTError> {
         fight::open_position(
             env,
             owner,
@@ -844,11 +1318,11 @@ impl NFT {
         )
     }
 
-    pub fn close_position(env: Env, owner: Address, category: Category, token_id: TokenId) {
+    pub fn close_position(env: Env, owner: Address, category: Category, token_id: TokenId) -> Result<(), NFTError> {
         fight::close_position(env, owner, category, token_id)
     }
 
-    pub fn currency_price(env: Env, oracle_contract_id: Address) -> i128 {
+    pub fn currency_price(env: Env, oracle_contract_id: Address) -> Result<i128, NFTError> {
         fight::get_currency_price(env, oracle_contract_id, fight::FightCurrency::BTC)
     }
 
@@ -857,7 +1331,7 @@ impl NFT {
         user: Address,
         category: Category,
         token_id: TokenId,
-    ) -> fight::Fight {
+    ) -> Result<fight::Fight, NFTError> {
         fight::read_fight(env, user, category, token_id)
     }
 
@@ -865,26 +1339,32 @@ impl NFT {
         fight::read_fights(env)
     }
 
-    pub fn check_liquidation(env: Env, liquidator: Address, user: Address, category: Category, token_id: TokenId) {
+    pub fn check_liquidation(
+        env: Env,
+        liquidator: Address,
+        user: Address,
+        category: Category,
+        token_id: TokenId,
+    ) -> Result<(), NFTError> {
         fight::check_liquidation(env, liquidator, user, category, token_id)
     }
 }
 
 #[contractimpl]
 impl NFT {
-    pub fn lend(env: Env, lender: Address, category: Category, token_id: TokenId, power: u32) {
+    pub fn lend(env: Env, lender: Address, category: Category, token_id: TokenId, power: u32) -> Result<(), NFTError> {
         lending::lend(env, lender, category, token_id, power)
     }
 
-    pub fn borrow(env: Env, borrower: Address, category: Category, token_id: TokenId, power: u32) {
+    pub fn borrow(env: Env, borrower: Address, category: Category, token_id: TokenId, power: u32) -> Result<(), NFTError> {
         lending::borrow(env, borrower, category, token_id, power)
     }
 
-    pub fn repay(env: Env, borrower: Address, category: Category, token_id: TokenId) {
+    pub fn repay(env: Env, borrower: Address, category: Category, token_id: TokenId) -> Result<(), NFTError> {
         lending::repay(env, borrower, category, token_id)
     }
 
-    pub fn withdraw(env: Env, lender: Address, category: Category, token_id: TokenId) {
+    pub fn withdraw(env: Env, lender: Address, category: Category, token_id: TokenId) -> Result<(), NFTError> {
         lending::withdraw(env, lender, category, token_id)
     }
 
@@ -898,7 +1378,7 @@ impl NFT {
         category: Category,
         token_id: TokenId,
         power: u32,
-    ) -> lending::BorrowQuote {
+    ) -> Result<lending::BorrowQuote, NFTError> {
         lending::borrow_quote(env, borrower, category, token_id, power)
     }
 
@@ -907,7 +1387,7 @@ impl NFT {
         player: Address,
         category: Category,
         token_id: TokenId,
-    ) -> lending::Lending {
+    ) -> Result<lending::Lending, NFTError> {
         lending::read_lending(env, player, category, token_id)
     }
 
@@ -916,7 +1396,7 @@ impl NFT {
         player: Address,
         category: Category,
         token_id: TokenId,
-    ) -> lending::Borrowing {
+    ) -> Result<lending::Borrowing, NFTError> {
         lending::read_borrowing(env, player, category, token_id)
     }
 
@@ -935,15 +1415,15 @@ impl NFT {
 
 #[contractimpl]
 impl NFT {
-    pub fn place(env: Env, owner: Address, token_id: TokenId) {
-        deck::place(env, owner, token_id);
+    pub fn place(env: Env, owner: Address, token_id: TokenId) -> Result<(), NFTError> {
+        deck::place(env, owner, token_id)
     }
 
-    pub fn replace(env: Env, owner: Address, prev_token_id: TokenId, token_id: TokenId) {
-        deck::replace(env, owner, prev_token_id, token_id);
+    pub fn replace(env: Env, owner: Address, prev_token_id: TokenId, token_id: TokenId) -> Result<(), NFTError> {
+        deck::replace(env, owner, prev_token_id, token_id)
     }
 
-    pub fn remove_place(env: Env, owner: Address, token_id: TokenId) {
+    pub fn remove_place(env: Env, owner: Address, token_id: TokenId) -> Result<(), NFTError> {
         deck::remove_place(env, owner, token_id)
     }
 
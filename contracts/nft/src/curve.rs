@@ -0,0 +1,141 @@
+use crate::admin::{read_administrator, read_config};
+use crate::error::NFTError;
+use crate::event::emit_price_curve_set;
+use crate::governance::timelock::ensure_no_timelock;
+use crate::metadata::has_metadata;
+use crate::storage_types::DataKey2;
+use soroban_sdk::{contracttype, Env};
+
+/// Fixed-point scale for exponential growth.
+const CURVE_SCALE: i128 = 1_000_000_000;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurveKind {
+    /// Each card in supply adds `step_bps` of the template price.
+    Linear,
+    /// Each card in supply multiplies the price by `1 + step_bps / 10000`.
+    Exponential,
+}
+
+/// Scales a template's prices by its supply. All values are basis points of
+/// the template price, so one curve prices TERRY, XTAR and USD alike.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceCurve {
+    pub kind: CurveKind,
+    pub step_bps: u32,
+    pub floor_bps: u32,
+    pub ceiling_bps: u32,
+    /// Burns pay out from the curve price of the last mint instead of the template price.
+    pub refund_on_burn: bool,
+}
+
+pub fn read_curve(env: &Env, token_id: u32) -> Option<PriceCurve> {
+    env.storage().persistent().get(&DataKey2::PriceCurve(token_id))
+}
+
+/// Sets or removes (`None`) a template's curve. Admin only, and only while no
+/// timelock delay is configured; otherwise queue a
+/// `ConfigChange::PriceCurve` or `ConfigChange::ClearPriceCurve`.
+pub fn set_curve(env: Env, token_id: u32, curve: Option<PriceCurve>) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    ensure_no_timelock(&read_config(&env))?;
+    validate_curve(&env, token_id, &curve)?;
+    write_curve(&env, token_id, &curve);
+    Ok(())
+}
+
+pub fn validate_curve(env: &Env, token_id: u32, curve: &Option<PriceCurve>) -> Result<(), NFTError> {
+    if !has_metadata(env, token_id) {
+        return Err(NFTError::MetadataNotFound);
+    }
+    match curve {
+        Some(c) if c.ceiling_bps == 0 || c.floor_bps > c.ceiling_bps => Err(NFTError::InvalidPercentage),
+        _ => Ok(()),
+    }
+}
+
+pub fn write_curve(env: &Env, token_id: u32, curve: &Option<PriceCurve>) {
+    let key = DataKey2::PriceCurve(token_id);
+    match curve {
+        Some(c) => env.storage().persistent().set(&key, c),
+        None => env.storage().persistent().remove(&key),
+    }
+    emit_price_curve_set(env, token_id, curve);
+}
+
+/// Cards of a template currently in existence: mints minus burns.
+pub fn read_supply(env: &Env, token_id: u32) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TemplateSupply(token_id))
+        .unwrap_or(0)
+}
+
+pub fn record_mint(env: &Env, token_id: u32) {
+    let supply = read_supply(env, token_id) + 1;
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TemplateSupply(token_id), &supply);
+}
+
+pub fn record_burn(env: &Env, token_id: u32) {
+    let supply = read_supply(env, token_id).saturating_sub(1);
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TemplateSupply(token_id), &supply);
+}
+
+/// `base^exp` in CURVE_SCALE fixed point, saturating at `cap`.
+fn pow_capped(base: i128, mut exp: u32, cap: i128) -> i128 {
+    let mut result = CURVE_SCALE;
+    let mut factor = base.min(cap);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = (result * factor / CURVE_SCALE).min(cap);
+        }
+        exp >>= 1;
+        if exp > 0 {
+            factor = (factor * factor / CURVE_SCALE).min(cap);
+        }
+    }
+    result
+}
+
+/// Price multiplier, in basis points, at a given supply.
+pub fn multiplier_bps(curve: &PriceCurve, supply: u32) -> i128 {
+    let floor = curve.floor_bps as i128;
+    let ceiling = curve.ceiling_bps as i128;
+    let bps = match curve.kind {
+        CurveKind::Linear => 10000 + curve.step_bps as i128 * supply as i128,
+        CurveKind::Exponential => {
+            let growth = CURVE_SCALE + curve.step_bps as i128 * CURVE_SCALE / 10000;
+            let cap = ceiling * CURVE_SCALE / 10000;
+            pow_capped(growth, supply, cap) * 10000 / CURVE_SCALE
+        }
+    };
+    bps.clamp(floor, ceiling)
+}
+
+/// `price` scaled by the template's curve at `supply`; unchanged without a curve.
+pub fn apply(env: &Env, token_id: u32, price: i128, supply: u32) -> i128 {
+    match read_curve(env, token_id) {
+        Some(curve) => price * multiplier_bps(&curve, supply) / 10000,
+        None => price,
+    }
+}
+
+/// TERRY price a burn pays out from: the curve price of the last mint when the
+/// curve refunds along it, the template price otherwise.
+pub fn burn_price_terry(env: &Env, token_id: u32, price_terry: i128) -> i128 {
+    match read_curve(env, token_id) {
+        Some(curve) if curve.refund_on_burn => {
+            let supply = read_supply(env, token_id).saturating_sub(1);
+            price_terry * multiplier_bps(&curve, supply) / 10000
+        }
+        _ => price_terry,
+    }
+}
@@ -1,15 +1,5 @@
 use soroban_sdk::{self, contracterror};
 
-#[contracterror]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
-#[repr(u32)]
-pub enum MyLabError {
-    NotOwner = 300,
-    NotNFT = 301,
-    NotAuthorized = 302,
-    OutOfBounds = 303,
-}
-
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -18,4 +8,93 @@ pub enum NFTError {
     RoundAlreadyProcessed = 2,
     NotAuthorized = 3,
     NoRewardsAvailable = 4,
+    // Config validation
+    InvalidPercentage = 5,
+    StakeScheduleMismatch = 6,
+    InvalidConversionRate = 7,
+    FeeTooHigh = 8,
+    NegativeAmount = 9,
+    // Timelocked parameter changes
+    TimelockRequired = 10,
+    TimelockTooShort = 11,
+    TimelockNotReady = 12,
+    ChangeNotFound = 13,
+    // Governance
+    NotProposer = 14,
+    ProposalNotFound = 15,
+    ProposalNotActive = 16,
+    VotingClosed = 17,
+    VotingOpen = 18,
+    AlreadyVoted = 19,
+    NoVotingPower = 20,
+    InvalidGovernanceSettings = 21,
+    // Cards and minting
+    CardNotFound = 22,
+    CardLocked = 23,
+    CardAlreadyOwned = 24,
+    MetadataNotFound = 25,
+    LevelTooLow = 26,
+    InsufficientTerry = 27,
+    InsufficientPower = 28,
+    InvalidAmount = 29,
+    InvalidCategory = 30,
+    Overflow = 31,
+    MismatchedLengths = 32,
+    BatchTooLarge = 33,
+    // Stake
+    InvalidStakePeriod = 34,
+    StakeNotFound = 35,
+    StakeLocked = 36,
+    // Fight
+    InvalidLeverage = 37,
+    InvalidOraclePrice = 38,
+    StaleOracle = 39,
+    FightNotFound = 40,
+    NotLiquidatable = 41,
+    // Lend and borrow
+    LendingNotFound = 42,
+    BorrowingNotFound = 43,
+    InsufficientLiquidity = 44,
+    ExceedsCollateral = 45,
+    InvalidHorizon = 46,
+    // Deck
+    DeckFull = 47,
+    DeckEmpty = 48,
+    CardNotInDeck = 49,
+    // Treasury
+    InsufficientTreasury = 50,
+    // At the contracterror variant limit: new failures go in `EconomyError`
+}
+
+/// Failures of the treasury, sale, pricing and pot subsystems. Codes start at 100
+/// so they never overlap `NFTError`. Every entry point returns one of the two
+/// enums; a failure from the other one traps with `panic_with_error!`, which
+/// still hands the caller its code.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum EconomyError {
+    // Card sales
+    SaleClosed = 100,
+    NotWhitelisted = 101,
+    WalletLimitExceeded = 102,
+    // Pricing
+    UnknownPaymentToken = 103,
+    UsdPriceNotSet = 104,
+    // Batched pot openings
+    OpeningInProgress = 105,
+    NoOpeningInProgress = 106,
+    OpeningIncomplete = 107,
+    InvalidBatchSize = 108,
+    // Sponsor tokens
+    TokenAlreadyRegistered = 109,
+    TokenNotRegistered = 110,
+    TokenPolicyUnsupported = 115,
+    // Upgrades
+    MigrationPending = 111,
+    // Keeper rounds
+    RoundDurationNotSet = 112,
+    RoundNotOver = 113,
+    // Minting
+    SlippageExceeded = 114,
 }
@@ -1,34 +1,91 @@
-use crate::storage_types::{PendingReward, PlayerReward, PotSnapshot};
-use crate::nft_info::{Action};
-use soroban_sdk::{Address, Env, BytesN, String, symbol_short};
+use crate::curve::PriceCurve;
+use crate::fees::{ActionFees, FeeRecipient};
+use crate::actions::fight::{Asset as OracleAsset, Fight};
+use crate::governance::proposal::{Proposal, ProposalStatus};
+use crate::governance::timelock::{ConfigChange, QueuedChange};
+use crate::ledger::Asset;
+use crate::metadata::{read_metadata, CardMetadata};
+use crate::pot::modes::{DistributionMode, PotWinner};
+use crate::pot::opening::OpeningProgress;
+use crate::sale::SaleConfig;
+use crate::storage_types::{Config, PendingReward, PotAmounts, PotSnapshot, State, TokenId, User};
+use crate::nft_info::{Action, Category};
+use soroban_sdk::{contractevent, contracttype, Address, Env, Vec};
+
+/// Schema version carried by every event payload. Bump it whenever a payload changes shape.
+pub const EVENT_VERSION: u32 = 1;
+
+/// Payload shared by all card action events.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardEvent {
+    pub version: u32,
+    pub token_id: u32,
+    pub category: Category,
+    /// POWER moved by the action, before fees.
+    pub power: u32,
+    /// POWER taken as action fee.
+    pub power_fee: u32,
+    /// Net TERRY change for the player (negative when paid).
+    pub terry: i128,
+    /// Net XTAR change for the player (negative when paid).
+    pub xtar: i128,
+    /// Power left on the card after the action.
+    pub card_power: u32,
+}
+
+/// Builds an empty card event for `token_id`, using the category from its metadata.
+pub fn card_event(env: &Env, token_id: &TokenId) -> CardEvent {
+    CardEvent {
+        version: EVENT_VERSION,
+        token_id: token_id.0,
+        category: read_metadata(env, token_id.0).category,
+        power: 0,
+        power_fee: 0,
+        terry: 0,
+        xtar: 0,
+        card_power: 0,
+    }
+}
 
 // Event Emission
+/// Published when the pot is opened.
+#[contractevent(topics = ["pot_open"])]
+pub struct PotOpened {
+    #[topic]
+    pub round: u32,
+    pub version: u32,
+    pub total_terry: i128,
+    pub total_power: u32,
+    pub total_xtar: i128,
+    pub total_participants: u32,
+    pub total_effective_power: u32,
+}
+
 /// Emits an event when the pot is opened.
 pub fn emit_pot_opened(env: &Env, round: u32, snapshot: &PotSnapshot) {
-    env.events().publish(
-        (symbol_short!("pot_open"), round),
-        (
-            snapshot.total_terry,
-            snapshot.total_power,
-            snapshot.total_xtar,
-            snapshot.total_participants,
-            snapshot.total_effective_power,
-        ),
-    );
-}
-
-/// Emits an event when a player's share is calculated.
-pub fn emit_share_calculated(env: &Env, player: &Address, reward: &PlayerReward) {
-    env.events().publish(
-        (symbol_short!("share_cal"), player.clone()),
-        (
-            reward.round_number,
-            reward.share_percentage,
-            reward.effective_power,
-            reward.deck_bonus,
-            reward.deck_categories,
-        ),
-    );
+    PotOpened {
+        round,
+        version: EVENT_VERSION,
+        total_terry: snapshot.total_terry,
+        total_power: snapshot.total_power,
+        total_xtar: snapshot.total_xtar,
+        total_participants: snapshot.total_participants,
+        total_effective_power: snapshot.total_effective_power,
+    }
+    .publish(env);
+}
+
+/// Published when Dogstar fees are accumulated.
+#[contractevent(topics = ["fee_acc"])]
+pub struct DogstarFeeAccumulated {
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+    pub fee_percentage: u32,
+    pub from: Option<Address>,
+    pub action: Action,
 }
 
 /// Emits an event when Dogstar fees are accumulated.
@@ -41,21 +98,27 @@ pub fn emit_dogstar_fee_accumulated(
     from: Option<Address>,
     action: Option<Action>
 ) {
-    let action_val = action.unwrap_or(Action::None);
-    
-    // let demo_account_strkey = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
-    
-    // Convert &str to soroban_sdk::String
-    // let demo_address_string = String::from_str(&env, demo_account_strkey); 
-    
-    // Use the soroban_sdk::String to create the Address
-    // let demo_address = Address::from_string(&demo_address_string);
-    // let from_val = from.unwrap_or(Address::from_string(String::from(&"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF")));
-    
-    env.events().publish(
-        (symbol_short!("fee_acc"),),
-        (terry, power, xtar, fee_percentage, from, action_val),
-    );
+    DogstarFeeAccumulated {
+        version: EVENT_VERSION,
+        terry,
+        power,
+        xtar,
+        fee_percentage,
+        from,
+        action: action.unwrap_or(Action::None),
+    }
+    .publish(env);
+}
+
+/// Published when Dogstar fees are withdrawn.
+#[contractevent(topics = ["fee_wd"])]
+pub struct DogstarFeeWithdrawn {
+    #[topic]
+    pub recipient: Address,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
 }
 
 /// Emits an event when Dogstar fees are withdrawn.
@@ -66,174 +129,1280 @@ pub fn emit_dogstar_fee_withdrawn(
     power: u32,
     xtar: i128,
 ) {
-    env.events().publish(
-        (symbol_short!("fee_wd"), recipient.clone()),
-        (terry, power, xtar),
-    );
+    DogstarFeeWithdrawn {
+        recipient: recipient.clone(),
+        version: EVENT_VERSION,
+        terry,
+        power,
+        xtar,
+    }
+    .publish(env);
+}
+
+/// Published when the Dogstar fee percentage is updated.
+#[contractevent(topics = ["fee_pct"])]
+pub struct DogstarFeePercentageUpdated {
+    pub version: u32,
+    pub old_fee: u32,
+    pub new_fee: u32,
 }
 
 /// Emits an event when the Dogstar fee percentage is updated.
 pub fn emit_dogstar_fee_percentage_updated(env: &Env, old_fee: u32, new_fee: u32) {
-    env.events()
-        .publish((symbol_short!("fee_pct"),), (old_fee, new_fee));
+    DogstarFeePercentageUpdated {
+        version: EVENT_VERSION,
+        old_fee,
+        new_fee,
+    }
+    .publish(env);
 }
 
-/// Emits an event when a reward is claimed.
-pub fn emit_reward_claimed(e: &Env, player: &Address, reward: &PendingReward) {
-    e.events().publish(
-        (symbol_short!("rwd_cld"), player.clone(), reward.round_number),
-        (reward.terry_amount, reward.power_amount, reward.xtar_amount),
-    );
+/// Published when a reward is marked as pending due to a missing trustline.
+#[contractevent(topics = ["reward_pd"])]
+pub struct RewardPending {
+    #[topic]
+    pub player: Address,
+    #[topic]
+    pub round: u32,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
 }
 
 /// Emits an event when a reward is marked as pending due to missing trustline.
 pub fn emit_reward_pending(e: &Env, player: &Address, reward: &PendingReward) {
-    e.events().publish(
-        (symbol_short!("reward_pd"), player.clone(), reward.round_number),
-        (reward.terry_amount, reward.power_amount, reward.xtar_amount),
-    );
+    RewardPending {
+        player: player.clone(),
+        round: reward.round_number,
+        version: EVENT_VERSION,
+        terry: reward.terry_amount,
+        power: reward.power_amount,
+        xtar: reward.xtar_amount,
+    }
+    .publish(e);
+}
+
+/// Published when rewards are claimed from the HAW AI pot.
+#[contractevent(topics = ["rwd_claim"])]
+pub struct RewardsClaimed {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
 }
 
 /// Emits an event when rewards are claimed from HAW AI pot.
 pub fn emit_rewards_claimed(e: &Env, player: &Address, terry: i128, power: u32, xtar: i128) {
-    e.events().publish(
-        (symbol_short!("rwd_claim"), player.clone()),
-        (terry, power, xtar),
-    );
+    RewardsClaimed {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        terry,
+        power,
+        xtar,
+    }
+    .publish(e);
+}
+
+/// Published when a card is burned.
+#[contractevent(topics = ["burn"], data_format = "single-value")]
+pub struct CardBurned {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is burned.
-pub fn emit_burn(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("burn"), player.clone()),
-        (),
-    );
+pub fn emit_burn(env: &Env, player: &Address, event: &CardEvent) {
+    CardBurned {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is staked.
+#[contractevent(topics = ["stake", "open"], data_format = "single-value")]
+pub struct StakeOpened {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is staked.
-pub fn emit_stake(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("stake"), symbol_short!("open"), player.clone()),
-        (),
-    );
+pub fn emit_stake(env: &Env, player: &Address, event: &CardEvent) {
+    StakeOpened {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when stake power is increased.
+#[contractevent(topics = ["stake", "increase"], data_format = "single-value")]
+pub struct StakeIncreased {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when stake power is increased.
-pub fn emit_stake_increased(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("stake"), symbol_short!("increase"), player.clone()),
-        (),
-    );
+pub fn emit_stake_increased(env: &Env, player: &Address, event: &CardEvent) {
+    StakeIncreased {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is unstaked.
+#[contractevent(topics = ["stake", "close"], data_format = "single-value")]
+pub struct StakeClosed {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is unstaked.
-pub fn emit_unstake(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("stake"), symbol_short!("close"), player.clone()),
-        (),
-    );
+pub fn emit_unstake(env: &Env, player: &Address, event: &CardEvent) {
+    StakeClosed {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is lent.
+#[contractevent(topics = ["lend", "open"], data_format = "single-value")]
+pub struct LendOpened {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is lent.
-pub fn emit_lend(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("lend"), symbol_short!("open"), player.clone()),
-        (),
-    );
+pub fn emit_lend(env: &Env, player: &Address, event: &CardEvent) {
+    LendOpened {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when lending is withdrawn.
+#[contractevent(topics = ["lend", "close"], data_format = "single-value")]
+pub struct LendClosed {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when lending is withdrawn.
-pub fn emit_withdraw(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("lend"), symbol_short!("close"), player.clone()),
-        (),
-    );
+pub fn emit_withdraw(env: &Env, player: &Address, event: &CardEvent) {
+    LendClosed {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when borrowing is made.
+#[contractevent(topics = ["borrow", "open"], data_format = "single-value")]
+pub struct BorrowOpened {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when borrowing is made.
-pub fn emit_borrow(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("borrow"), symbol_short!("open"), player.clone()),
-        (),
-    );
+pub fn emit_borrow(env: &Env, player: &Address, event: &CardEvent) {
+    BorrowOpened {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when repayment is made.
+#[contractevent(topics = ["borrow", "close"], data_format = "single-value")]
+pub struct BorrowClosed {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when repayment is made.
-pub fn emit_repay(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("borrow"), symbol_short!("close"), player.clone()),
-        (),
-    );
+pub fn emit_repay(env: &Env, player: &Address, event: &CardEvent) {
+    BorrowClosed {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when the liquidation index is updated (lazy pro-rata) because a
+/// lender withdrew more interest than the pool holds.
+#[contractevent(topics = ["idx_upd"])]
+pub struct IndexUpdated {
+    #[topic]
+    pub lender: Address,
+    pub version: u32,
+    pub token_id: u32,
+    /// Interest owed to the withdrawing lender.
+    pub interest: u64,
+    pub l_index: u64,
+    pub d_l: u64,
+    pub deficit: u64,
+    pub w_total: u64,
 }
 
 /// Emits an event when the liquidation index is updated (lazy pro‑rata)
-pub fn emit_index_updated(env: &Env, l_index: u64, d_l: u64, deficit: u64, w_total: u64) {
-    env.events().publish(
-        (symbol_short!("idx_upd"),),
-        (l_index, d_l, deficit, w_total),
-    );
+pub fn emit_index_updated(
+    env: &Env,
+    lender: &Address,
+    token_id: &TokenId,
+    interest: u64,
+    d_l: u64,
+    deficit: u64,
+    state: &State,
+) {
+    IndexUpdated {
+        lender: lender.clone(),
+        version: EVENT_VERSION,
+        token_id: token_id.0,
+        interest,
+        l_index: state.l_index,
+        d_l,
+        deficit,
+        w_total: state.w_total,
+    }
+    .publish(env);
+}
+
+/// Published when a loan is touched and a haircut is applied.
+#[contractevent(topics = ["loan_tch"])]
+pub struct LoanTouched {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub token_id: u32,
+    pub haircut: u32,
+    pub reserve_left: u32,
+    pub ownership_lost: bool,
 }
 
 /// Emits an event when a loan is touched and a haircut is applied
-pub fn emit_loan_touched(env: &Env, player: &Address, haircut: u32, reserve_left: u32, ownership_lost: bool) {
-    env.events().publish(
-        (symbol_short!("loan_tch"), player.clone()),
-        (haircut, reserve_left, ownership_lost),
-    );
+pub fn emit_loan_touched(
+    env: &Env,
+    player: &Address,
+    token_id: &TokenId,
+    haircut: u32,
+    reserve_left: u32,
+    ownership_lost: bool,
+) {
+    LoanTouched {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        token_id: token_id.0,
+        haircut,
+        reserve_left,
+        ownership_lost,
+    }
+    .publish(env);
+}
+
+/// Published when a loan is fully liquidated.
+#[contractevent(topics = ["loan_liq"])]
+pub struct LoanLiquidated {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub category: Category,
+    pub token_id: u32,
+    /// Collateral POWER cut by the final haircut.
+    pub power: u32,
 }
 
 /// Emits an event when a loan is fully liquidated
-pub fn emit_loan_liquidated(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("loan_liq"), player.clone()),
-        (),
-    );
+pub fn emit_loan_liquidated(env: &Env, player: &Address, category: &Category, token_id: &TokenId, power: u32) {
+    LoanLiquidated {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        category: category.clone(),
+        token_id: token_id.0,
+        power,
+    }
+    .publish(env);
+}
+
+/// Published when a card is placed in a deck.
+#[contractevent(topics = ["deck", "place"], data_format = "single-value")]
+pub struct DeckPlaced {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is placed in a deck.
-pub fn emit_deck_place(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("deck"), symbol_short!("place"), player.clone()),
-        (),
-    );
+pub fn emit_deck_place(env: &Env, player: &Address, event: &CardEvent) {
+    DeckPlaced {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is replaced in a deck.
+#[contractevent(topics = ["deck", "replace"])]
+pub struct DeckReplaced {
+    #[topic]
+    pub player: Address,
+    pub prev_token_id: u32,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is replaced in a deck.
-pub fn emit_deck_replace(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("deck"), symbol_short!("replace"), player.clone()),
-        (),
-    );
+pub fn emit_deck_replace(env: &Env, player: &Address, prev_token_id: u32, event: &CardEvent) {
+    DeckReplaced {
+        player: player.clone(),
+        prev_token_id,
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is removed from a deck.
+#[contractevent(topics = ["deck", "remove"], data_format = "single-value")]
+pub struct DeckRemoved {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is removed from a deck.
-pub fn emit_deck_remove(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("deck"), symbol_short!("remove"), player.clone()),
-        (),
-    );
+pub fn emit_deck_remove(env: &Env, player: &Address, event: &CardEvent) {
+    DeckRemoved {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a deck is completed (4 cards).
+#[contractevent(topics = ["deck", "complete"])]
+pub struct DeckCompleted {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub total_power: u32,
+    pub bonus: u32,
+    pub categories: u32,
 }
 
 /// Emits an event when a deck is completed (4 cards).
-pub fn emit_deck_completed(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("deck"), symbol_short!("complete"), player.clone()),
-        (),
-    );
+pub fn emit_deck_completed(env: &Env, player: &Address, total_power: u32, bonus: u32, categories: u32) {
+    DeckCompleted {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        total_power,
+        bonus,
+        categories,
+    }
+    .publish(env);
+}
+
+/// Published when a card is minted.
+#[contractevent(topics = ["mint"], data_format = "single-value")]
+pub struct CardMinted {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is minted.
-pub fn emit_mint(env: &Env, player: &Address) {
-    env.events().publish(
-        (symbol_short!("mint"), player.clone()),
-        (),
-    );
+pub fn emit_mint(env: &Env, player: &Address, event: &CardEvent) {
+    CardMinted {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card is transferred.
+#[contractevent(topics = ["transfer"], data_format = "single-value")]
+pub struct CardTransferred {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub event: CardEvent,
 }
 
 /// Emits an event when a card is transferred.
-pub fn emit_transfer(env: &Env, from: &Address, to: &Address) {
-    env.events().publish(
-        (symbol_short!("transfer"), from.clone(), to.clone()),
-        (),
-    );
+pub fn emit_transfer(env: &Env, from: &Address, to: &Address, event: &CardEvent) {
+    CardTransferred {
+        from: from.clone(),
+        to: to.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a fight position is opened.
+#[contractevent(topics = ["fight", "open"], data_format = "single-value")]
+pub struct FightOpened {
+    #[topic]
+    pub player: Address,
+    pub event: CardEvent,
+}
+
+/// Emits an event when a fight position is opened.
+pub fn emit_fight_open(env: &Env, player: &Address, event: &CardEvent) {
+    FightOpened {
+        player: player.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a fight position is closed by its owner.
+#[contractevent(topics = ["fight", "close"])]
+pub struct FightClosed {
+    #[topic]
+    pub player: Address,
+    pub pnl_power: i128,
+    pub event: CardEvent,
+}
+
+/// Emits an event when a fight position is closed by its owner.
+pub fn emit_fight_close(env: &Env, player: &Address, pnl_power: i128, event: &CardEvent) {
+    FightClosed {
+        player: player.clone(),
+        pnl_power,
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a fight position is liquidated.
+#[contractevent(topics = ["fight", "liquidate"])]
+pub struct FightLiquidated {
+    #[topic]
+    pub player: Address,
+    pub liquidator: Address,
+    pub event: CardEvent,
+}
+
+/// Emits an event when a fight position is liquidated.
+pub fn emit_fight_liquidated(env: &Env, player: &Address, liquidator: &Address, event: &CardEvent) {
+    FightLiquidated {
+        player: player.clone(),
+        liquidator: liquidator.clone(),
+        event: event.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a fight position record is written, with the full record.
+#[contractevent(topics = ["fight", "stored"])]
+pub struct FightStored {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub fight: Fight,
+}
+
+/// Emits an event when a fight position record is written.
+pub fn emit_fight_stored(env: &Env, fight: &Fight) {
+    FightStored {
+        player: fight.owner.clone(),
+        version: EVENT_VERSION,
+        fight: fight.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a fight position record is removed, with the removed record.
+#[contractevent(topics = ["fight", "removed"])]
+pub struct FightRemoved {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub fight: Fight,
+}
+
+/// Emits an event when a fight position record is removed.
+pub fn emit_fight_removed(env: &Env, fight: &Fight) {
+    FightRemoved {
+        player: fight.owner.clone(),
+        version: EVENT_VERSION,
+        fight: fight.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when the contract is initialized.
+#[contractevent(topics = ["initialized"])]
+pub struct Initialized {
+    pub version: u32,
+    pub admin: Address,
+}
+
+/// Emits an event when the contract is initialized.
+pub fn emit_initialized(env: &Env, admin: &Address) {
+    Initialized {
+        version: EVENT_VERSION,
+        admin: admin.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when the admin hands the contract to a new admin.
+#[contractevent(topics = ["set_admin"])]
+pub struct AdminChanged {
+    #[topic]
+    pub admin: Address,
+    pub version: u32,
+    pub new_admin: Address,
+}
+
+/// Emits an event when the admin hands the contract to a new admin.
+pub fn emit_admin_changed(env: &Env, admin: &Address, new_admin: &Address) {
+    AdminChanged {
+        admin: admin.clone(),
+        version: EVENT_VERSION,
+        new_admin: new_admin.clone(),
+    }
+    .publish(env);
+}
+
+/// Published whenever the lending and staking state is written.
+#[contractevent(topics = ["state"])]
+pub struct StateUpdated {
+    pub version: u32,
+    pub state: State,
+}
+
+/// Emits an event when the lending and staking state is written.
+pub fn emit_state_updated(env: &Env, state: &State) {
+    StateUpdated {
+        version: EVENT_VERSION,
+        state: state.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a player reaches a new level.
+#[contractevent(topics = ["level_up"])]
+pub struct LevelUp {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub old_level: u32,
+    pub new_level: u32,
+}
+
+/// Emits an event when a player reaches a new level.
+pub fn emit_level_up(env: &Env, player: &Address, old_level: u32, new_level: u32) {
+    LevelUp {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        old_level,
+        new_level,
+    }
+    .publish(env);
+}
+
+/// Published when the admin replaces the config.
+#[contractevent(topics = ["cfg_upd"])]
+pub struct ConfigUpdated {
+    pub version: u32,
+    pub config: Config,
+}
+
+/// Emits an event when the admin replaces the config.
+pub fn emit_config_updated(env: &Env, config: &Config) {
+    ConfigUpdated {
+        version: EVENT_VERSION,
+        config: config.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a member is added to or removed from the whitelist.
+#[contractevent(topics = ["whitelist"])]
+pub struct WhitelistUpdated {
+    #[topic]
+    pub member: Address,
+    pub version: u32,
+    pub whitelisted: bool,
+}
+
+/// Emits an event when a member is added to or removed from the whitelist.
+pub fn emit_whitelist_updated(env: &Env, member: &Address, whitelisted: bool) {
+    WhitelistUpdated {
+        member: member.clone(),
+        version: EVENT_VERSION,
+        whitelisted,
+    }
+    .publish(env);
+}
+
+/// Published when card metadata is created or overwritten.
+#[contractevent(topics = ["meta_new"])]
+pub struct MetadataCreated {
+    #[topic]
+    pub id: u32,
+    pub version: u32,
+    pub metadata: CardMetadata,
+}
+
+/// Emits an event when card metadata is created or overwritten.
+pub fn emit_metadata_created(env: &Env, id: u32, metadata: &CardMetadata) {
+    MetadataCreated {
+        id,
+        version: EVENT_VERSION,
+        metadata: metadata.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a user is created.
+#[contractevent(topics = ["user_new"])]
+pub struct UserCreated {
+    #[topic]
+    pub owner: Address,
+    pub version: u32,
+    pub power: u32,
+    pub terry: i128,
+    pub level: u32,
+}
+
+/// Emits an event when a user is created.
+pub fn emit_user_created(env: &Env, user: &User) {
+    UserCreated {
+        owner: user.owner.clone(),
+        version: EVENT_VERSION,
+        power: user.power,
+        terry: user.terry,
+        level: user.level,
+    }
+    .publish(env);
+}
+
+/// Published when a parameter change is queued behind the timelock.
+#[contractevent(topics = ["chg_sched"])]
+pub struct ChangeScheduled {
+    #[topic]
+    pub id: u32,
+    pub version: u32,
+    pub change: ConfigChange,
+    pub eta: u64,
+}
+
+/// Emits an event when a parameter change is queued behind the timelock.
+pub fn emit_change_scheduled(env: &Env, queued: &QueuedChange) {
+    ChangeScheduled {
+        id: queued.id,
+        version: EVENT_VERSION,
+        change: queued.change.clone(),
+        eta: queued.eta,
+    }
+    .publish(env);
+}
+
+/// Published when a queued parameter change is applied.
+#[contractevent(topics = ["chg_exec"])]
+pub struct ChangeExecuted {
+    #[topic]
+    pub id: u32,
+    pub version: u32,
+    pub change: ConfigChange,
+}
+
+/// Emits an event when a queued parameter change is applied.
+pub fn emit_change_executed(env: &Env, queued: &QueuedChange) {
+    ChangeExecuted {
+        id: queued.id,
+        version: EVENT_VERSION,
+        change: queued.change.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a queued parameter change is cancelled.
+#[contractevent(topics = ["chg_cncl"])]
+pub struct ChangeCancelled {
+    #[topic]
+    pub id: u32,
+    pub version: u32,
+    pub change: ConfigChange,
+}
+
+/// Emits an event when a queued parameter change is cancelled.
+pub fn emit_change_cancelled(env: &Env, queued: &QueuedChange) {
+    ChangeCancelled {
+        id: queued.id,
+        version: EVENT_VERSION,
+        change: queued.change.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a governance proposal is created.
+#[contractevent(topics = ["prop_new"])]
+pub struct ProposalCreated {
+    #[topic]
+    pub id: u32,
+    #[topic]
+    pub proposer: Address,
+    pub version: u32,
+    pub change: ConfigChange,
+    pub end_time: u64,
+    pub total_weight: u64,
+}
+
+/// Emits an event when a governance proposal is created.
+pub fn emit_proposal_created(env: &Env, proposal: &Proposal) {
+    ProposalCreated {
+        id: proposal.id,
+        proposer: proposal.proposer.clone(),
+        version: EVENT_VERSION,
+        change: proposal.change.clone(),
+        end_time: proposal.end_time,
+        total_weight: proposal.total_weight,
+    }
+    .publish(env);
+}
+
+/// Published when a player votes on a proposal.
+#[contractevent(topics = ["vote"])]
+pub struct VoteCast {
+    #[topic]
+    pub id: u32,
+    #[topic]
+    pub voter: Address,
+    pub version: u32,
+    pub support: bool,
+    pub weight: u64,
+}
+
+/// Emits an event when a player votes on a proposal.
+pub fn emit_vote_cast(env: &Env, voter: &Address, id: u32, support: bool, weight: u64) {
+    VoteCast {
+        id,
+        voter: voter.clone(),
+        version: EVENT_VERSION,
+        support,
+        weight,
+    }
+    .publish(env);
+}
+
+/// Published when voting on a proposal is closed.
+#[contractevent(topics = ["prop_done"])]
+pub struct ProposalFinalized {
+    #[topic]
+    pub id: u32,
+    pub version: u32,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub queued_change_id: Option<u32>,
+}
+
+/// Emits an event when voting on a proposal is closed.
+pub fn emit_proposal_finalized(env: &Env, proposal: &Proposal) {
+    ProposalFinalized {
+        id: proposal.id,
+        version: EVENT_VERSION,
+        status: proposal.status.clone(),
+        votes_for: proposal.votes_for,
+        votes_against: proposal.votes_against,
+        queued_change_id: proposal.queued_change_id,
+    }
+    .publish(env);
+}
+
+/// Published when the treasurer role is assigned or cleared.
+#[contractevent(topics = ["treasurer"])]
+pub struct TreasurerSet {
+    pub version: u32,
+    pub treasurer: Option<Address>,
+}
+
+/// Emits an event when the treasurer role is assigned or cleared.
+pub fn emit_treasurer_set(env: &Env, treasurer: Option<Address>) {
+    TreasurerSet {
+        version: EVENT_VERSION,
+        treasurer,
+    }
+    .publish(env);
+}
+
+/// Published when admin balance is withdrawn from the treasury.
+#[contractevent(topics = ["trsy_wd"])]
+pub struct TreasuryWithdrawn {
+    #[topic]
+    pub to: Address,
+    pub version: u32,
+    pub caller: Address,
+    pub asset: Asset,
+    pub amount: i128,
+}
+
+/// Emits an event when admin balance is withdrawn from the treasury.
+pub fn emit_treasury_withdrawn(env: &Env, caller: &Address, to: &Address, asset: Asset, amount: i128) {
+    TreasuryWithdrawn {
+        to: to.clone(),
+        version: EVENT_VERSION,
+        caller: caller.clone(),
+        asset,
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when Haw-AI balance is moved into the pot.
+#[contractevent(topics = ["trsy_swp"])]
+pub struct HawAiSwept {
+    pub version: u32,
+    pub caller: Address,
+    pub asset: Asset,
+    pub amount: i128,
+}
+
+/// Emits an event when Haw-AI balance is moved into the pot.
+pub fn emit_haw_ai_swept(env: &Env, caller: &Address, asset: Asset, amount: i128) {
+    HawAiSwept {
+        version: EVENT_VERSION,
+        caller: caller.clone(),
+        asset,
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when the fee recipients are replaced.
+#[contractevent(topics = ["fee_split"])]
+pub struct FeeRecipientsSet {
+    pub version: u32,
+    pub recipients: Vec<FeeRecipient>,
+}
+
+/// Emits an event when the fee recipients are replaced.
+pub fn emit_fee_recipients_set(env: &Env, recipients: &Vec<FeeRecipient>) {
+    FeeRecipientsSet {
+        version: EVENT_VERSION,
+        recipients: recipients.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a recipient's fee share is released for claiming.
+#[contractevent(topics = ["fee_rel"])]
+pub struct FeeShareReleased {
+    #[topic]
+    pub recipient: Address,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when a recipient's fee share is released for claiming.
+pub fn emit_fee_share_released(env: &Env, recipient: &Address, terry: i128, power: u32, xtar: i128) {
+    FeeShareReleased {
+        recipient: recipient.clone(),
+        version: EVENT_VERSION,
+        terry,
+        power,
+        xtar,
+    }
+    .publish(env);
+}
+
+/// Published when a recipient's fee share in a registered token is released.
+#[contractevent(topics = ["fee_rel_tk"])]
+pub struct FeeTokenShareReleased {
+    #[topic]
+    pub recipient: Address,
+    pub version: u32,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emits an event when a recipient's fee share in a registered token is released.
+pub fn emit_fee_token_share_released(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    FeeTokenShareReleased {
+        recipient: recipient.clone(),
+        version: EVENT_VERSION,
+        token: token.clone(),
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when a recipient claims its fee share in a registered token.
+#[contractevent(topics = ["fee_clm_tk"])]
+pub struct FeeTokenShareClaimed {
+    #[topic]
+    pub recipient: Address,
+    pub version: u32,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emits an event when a recipient claims its fee share in a registered token.
+pub fn emit_fee_token_share_claimed(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    FeeTokenShareClaimed {
+        recipient: recipient.clone(),
+        version: EVENT_VERSION,
+        token: token.clone(),
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when an action's fee schedule is set or cleared.
+#[contractevent(topics = ["fee_sched"])]
+pub struct ActionFeesUpdated {
+    #[topic]
+    pub action: Action,
+    pub version: u32,
+    pub fees: Option<ActionFees>,
+}
+
+/// Emits an event when an action's fee schedule is set or cleared.
+pub fn emit_action_fees_updated(env: &Env, action: &Action, fees: &Option<ActionFees>) {
+    ActionFeesUpdated {
+        action: action.clone(),
+        version: EVENT_VERSION,
+        fees: fees.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a token is registered for pot deposits.
+#[contractevent(topics = ["pot_tok"])]
+pub struct PotTokenRegistered {
+    pub version: u32,
+    pub token: Address,
+}
+
+/// Emits an event when a token is registered for pot deposits.
+pub fn emit_pot_token_registered(env: &Env, token: &Address) {
+    PotTokenRegistered {
+        version: EVENT_VERSION,
+        token: token.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a registered token is deposited into the pot.
+#[contractevent(topics = ["pot_dep"])]
+pub struct PotTokenDeposited {
+    #[topic]
+    pub from: Address,
+    pub version: u32,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emits an event when a registered token is deposited into the pot.
+pub fn emit_pot_token_deposited(env: &Env, from: &Address, token: &Address, amount: i128) {
+    PotTokenDeposited {
+        from: from.clone(),
+        version: EVENT_VERSION,
+        token: token.clone(),
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when a player claims a registered token share.
+#[contractevent(topics = ["pot_clm"])]
+pub struct PotTokenClaimed {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub token: Address,
+    pub amount: i128,
+}
+
+/// Emits an event when a player claims a registered token share.
+pub fn emit_pot_token_claimed(env: &Env, player: &Address, token: &Address, amount: i128) {
+    PotTokenClaimed {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        token: token.clone(),
+        amount,
+    }
+    .publish(env);
+}
+
+/// Published when a card template's USD price is set or cleared.
+#[contractevent(topics = ["usd_price"])]
+pub struct UsdPriceSet {
+    #[topic]
+    pub token_id: u32,
+    pub version: u32,
+    pub price_usd: Option<i128>,
+}
+
+/// Emits an event when a card template's USD price is set or cleared.
+pub fn emit_usd_price_set(env: &Env, token_id: u32, price_usd: Option<i128>) {
+    UsdPriceSet {
+        token_id,
+        version: EVENT_VERSION,
+        price_usd,
+    }
+    .publish(env);
+}
+
+/// Published when a token is accepted for USD-priced mints.
+#[contractevent(topics = ["pay_tok"])]
+pub struct PaymentTokenRegistered {
+    #[topic]
+    pub token: Address,
+    pub version: u32,
+    pub oracle_asset: OracleAsset,
+}
+
+/// Emits an event when a token is accepted for USD-priced mints.
+pub fn emit_payment_token_registered(env: &Env, token: &Address, oracle_asset: &OracleAsset) {
+    PaymentTokenRegistered {
+        token: token.clone(),
+        version: EVENT_VERSION,
+        oracle_asset: oracle_asset.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card template's sale schedule is set or removed.
+#[contractevent(topics = ["sale_cfg"])]
+pub struct SaleConfigured {
+    #[topic]
+    pub token_id: u32,
+    pub version: u32,
+    pub sale: Option<SaleConfig>,
+}
+
+/// Emits an event when a card template's sale schedule is set or removed.
+pub fn emit_sale_configured(env: &Env, token_id: u32, sale: &Option<SaleConfig>) {
+    SaleConfigured {
+        token_id,
+        version: EVENT_VERSION,
+        sale: sale.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a card template's price curve is set or removed.
+#[contractevent(topics = ["curve"])]
+pub struct PriceCurveSet {
+    #[topic]
+    pub token_id: u32,
+    pub version: u32,
+    pub curve: Option<PriceCurve>,
+}
+
+/// Emits an event when a card template's price curve is set or removed.
+pub fn emit_price_curve_set(env: &Env, token_id: u32, curve: &Option<PriceCurve>) {
+    PriceCurveSet {
+        token_id,
+        version: EVENT_VERSION,
+        curve: curve.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a keeper advances the pot to a new round.
+#[contractevent(topics = ["round_adv"])]
+pub struct RoundAdvanced {
+    #[topic]
+    pub keeper: Address,
+    pub version: u32,
+    pub round: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when a keeper advances the pot to a new round.
+pub fn emit_round_advanced(env: &Env, keeper: &Address, round: u32, terry: i128, power: u32, xtar: i128) {
+    RoundAdvanced {
+        keeper: keeper.clone(),
+        version: EVENT_VERSION,
+        round,
+        terry,
+        power,
+        xtar,
+    }
+    .publish(env);
+}
+
+/// Published when a batched pot opening begins, advances or is finalized.
+#[contractevent(topics = ["pot_batch"])]
+pub struct PotOpeningProgress {
+    #[topic]
+    pub round: u32,
+    pub version: u32,
+    pub processed: u32,
+    pub total: u32,
+    pub finalized: bool,
+}
+
+/// Emits an event when a batched pot opening begins, advances or is finalized.
+pub fn emit_pot_opening_progress(env: &Env, progress: &OpeningProgress) {
+    PotOpeningProgress {
+        round: progress.round,
+        version: EVENT_VERSION,
+        processed: progress.processed,
+        total: progress.total,
+        finalized: progress.finalized,
+    }
+    .publish(env);
+}
+
+/// Published when the distribution mode of a round is set.
+#[contractevent(topics = ["pot_mode"])]
+pub struct DistributionModeSet {
+    #[topic]
+    pub round: u32,
+    pub version: u32,
+    pub mode: DistributionMode,
+}
+
+/// Emits an event when the distribution mode of a round is set.
+pub fn emit_distribution_mode_set(env: &Env, round: u32, mode: &DistributionMode) {
+    DistributionModeSet {
+        round,
+        version: EVENT_VERSION,
+        mode: mode.clone(),
+    }
+    .publish(env);
+}
+
+/// Published when a tiered or lottery opening pays a winner.
+#[contractevent(topics = ["pot_win"])]
+pub struct PotWinnerPaid {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub round: u32,
+    pub share_bps: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when a tiered or lottery opening pays a winner.
+pub fn emit_pot_winner(env: &Env, round: u32, winner: &PotWinner, terry: i128, power: u32, xtar: i128) {
+    PotWinnerPaid {
+        player: winner.player.clone(),
+        version: EVENT_VERSION,
+        round,
+        share_bps: winner.share_bps,
+        terry,
+        power,
+        xtar,
+    }
+    .publish(env);
+}
+
+/// Published when the jackpot reserve is released into a round's pot.
+#[contractevent(topics = ["jackpot"])]
+pub struct JackpotReleased {
+    #[topic]
+    pub round: u32,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when the jackpot reserve is released into a round's pot.
+pub fn emit_jackpot_released(env: &Env, round: u32, jackpot: &PotAmounts) {
+    JackpotReleased {
+        round,
+        version: EVENT_VERSION,
+        terry: jackpot.terry,
+        power: jackpot.power,
+        xtar: jackpot.xtar,
+    }
+    .publish(env);
+}
+
+/// Published when a player's expired pot rewards are swept back into the pot.
+#[contractevent(topics = ["clm_exp"])]
+pub struct ClaimableExpired {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub round: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when a player's expired pot rewards are swept back into the pot.
+pub fn emit_claimable_expired(env: &Env, player: &Address, round: u32, swept: &PotAmounts) {
+    ClaimableExpired {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        round,
+        terry: swept.terry,
+        power: swept.power,
+        xtar: swept.xtar,
+    }
+    .publish(env);
+}
+
+/// Published when a player claims locked pot rewards early and forfeits a penalty to the pot.
+#[contractevent(topics = ["clm_pen"])]
+pub struct EarlyClaimPenalty {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+}
+
+/// Emits an event when a player claims locked pot rewards early and forfeits a penalty to the pot.
+pub fn emit_early_claim_penalty(env: &Env, player: &Address, penalty: &PotAmounts) {
+    EarlyClaimPenalty {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        terry: penalty.terry,
+        power: penalty.power,
+        xtar: penalty.xtar,
+    }
+    .publish(env);
+}
+
+/// Published when claimed pot POWER is compounded into deck cards or a stake.
+#[contractevent(topics = ["pwr_cmpd"])]
+pub struct PowerCompounded {
+    #[topic]
+    pub player: Address,
+    pub version: u32,
+    pub deck: u32,
+    pub stake: u32,
+    pub remainder: u32,
+}
+
+/// Emits an event when claimed pot POWER is compounded into deck cards or a stake.
+pub fn emit_power_compounded(env: &Env, player: &Address, deck: u32, stake: u32, remainder: u32) {
+    PowerCompounded {
+        player: player.clone(),
+        version: EVENT_VERSION,
+        deck,
+        stake,
+        remainder,
+    }
+    .publish(env);
 }
@@ -0,0 +1,361 @@
+use crate::admin::{
+    read_administrator, read_config, read_dogstar_claimable, write_dogstar_claimable,
+    MAX_DOGSTAR_FEE_PERCENTAGE,
+};
+use crate::error::NFTError;
+use crate::event::{
+    emit_action_fees_updated, emit_dogstar_fee_withdrawn, emit_fee_recipients_set,
+    emit_fee_share_released, emit_fee_token_share_claimed, emit_fee_token_share_released,
+};
+use crate::governance::timelock::ensure_no_timelock;
+use crate::ledger::{post, post_all, read_account, read_account_balance, Account, Asset};
+use crate::nft_info::Action;
+use crate::pot::management::get_current_round;
+use crate::pot::tokens::read_pot_tokens;
+use crate::storage_types::{DataKey2, UserClaimableBalance};
+use crate::user_info::{mint_terry, read_user, write_user};
+use soroban_sdk::{contracttype, token, vec, Address, Env, Vec};
+
+/// Most recipients a fee split may have.
+pub const MAX_FEE_RECIPIENTS: u32 = 10;
+
+/// Actions that can carry their own fees, in `fee_schedule` order.
+pub const FEE_ACTIONS: [Action; 8] = [
+    Action::None,
+    Action::Stake,
+    Action::Fight,
+    Action::Lend,
+    Action::Borrow,
+    Action::Burn,
+    Action::Deck,
+    Action::Mint,
+];
+
+/// Fees charged for one action.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionFees {
+    /// Share of pot inflows kept as protocol (Dogstar) fees, in basis points.
+    pub protocol_fee_bps: u32,
+    /// POWER fee taken by the action, in whole percent.
+    pub power_action_fee: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeScheduleEntry {
+    pub action: Action,
+    pub fees: ActionFees,
+    /// False when the action falls back to the global config fees.
+    pub custom: bool,
+}
+
+/// A share of the protocol (Dogstar) fees, in basis points.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeRecipient {
+    pub address: Address,
+    pub bps: u32,
+}
+
+/// Configured recipients. Without a configured split the admin receives all fees.
+pub fn read_fee_recipients(env: &Env) -> Vec<FeeRecipient> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::FeeRecipients)
+        .unwrap_or(vec![
+            env,
+            FeeRecipient {
+                address: read_administrator(env),
+                bps: 10000,
+            },
+        ])
+}
+
+/// Replaces the fee split. Admin only, and only while no timelock delay is
+/// configured; otherwise queue a `ConfigChange::FeeRecipients`.
+/// Applies to fees released from now on; released shares stay with their recipients.
+pub fn set_fee_recipients(env: Env, recipients: Vec<FeeRecipient>) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    ensure_no_timelock(&read_config(&env))?;
+    validate_fee_recipients(&recipients)?;
+    write_fee_recipients(&env, &recipients);
+    Ok(())
+}
+
+/// Fails with `InvalidPercentage` unless the list is non-empty, has no
+/// duplicates or zero shares, and sums to 10000 bps.
+pub fn validate_fee_recipients(recipients: &Vec<FeeRecipient>) -> Result<(), NFTError> {
+    if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+        return Err(NFTError::InvalidPercentage);
+    }
+    let mut total_bps: u32 = 0;
+    for (i, recipient) in recipients.iter().enumerate() {
+        if recipient.bps == 0 {
+            return Err(NFTError::InvalidPercentage);
+        }
+        let duplicate = recipients
+            .iter()
+            .skip(i + 1)
+            .any(|other| other.address == recipient.address);
+        if duplicate {
+            return Err(NFTError::InvalidPercentage);
+        }
+        total_bps += recipient.bps;
+    }
+    if total_bps != 10000 {
+        return Err(NFTError::InvalidPercentage);
+    }
+    Ok(())
+}
+
+pub fn write_fee_recipients(env: &Env, recipients: &Vec<FeeRecipient>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::FeeRecipients, recipients);
+    emit_fee_recipients_set(env, recipients);
+}
+
+pub fn validate_action_fees(fees: &ActionFees) -> Result<(), NFTError> {
+    // The borrow quote divides by (100 - power_action_fee)
+    if fees.power_action_fee >= 100 || fees.protocol_fee_bps > MAX_DOGSTAR_FEE_PERCENTAGE {
+        return Err(NFTError::FeeTooHigh);
+    }
+    Ok(())
+}
+
+fn read_action_fee_override(env: &Env, action: &Action) -> Option<ActionFees> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::ActionFeeOverride(action.clone()))
+}
+
+/// Fees in effect for an action: its own schedule if set, the config fees otherwise.
+pub fn read_action_fees(env: &Env, action: &Action) -> ActionFees {
+    read_action_fee_override(env, action).unwrap_or_else(|| {
+        let config = read_config(env);
+        ActionFees {
+            protocol_fee_bps: config.dogstar_fee_percentage,
+            power_action_fee: config.power_action_fee,
+        }
+    })
+}
+
+/// Stores or clears (`None`) an action's fees. Callers check authorization and validity.
+pub fn write_action_fees(env: &Env, action: &Action, fees: &Option<ActionFees>) {
+    let key = DataKey2::ActionFeeOverride(action.clone());
+    match fees {
+        Some(fees) => env.storage().persistent().set(&key, fees),
+        None => env.storage().persistent().remove(&key),
+    }
+    emit_action_fees_updated(env, action, fees);
+}
+
+/// Sets an action's fees directly. Only allowed while no timelock delay is configured;
+/// otherwise queue a `ConfigChange::ActionFees` or `ConfigChange::ClearActionFees`.
+pub fn set_action_fees(env: Env, action: Action, fees: Option<ActionFees>) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    ensure_no_timelock(&read_config(&env))?;
+    if let Some(fees) = &fees {
+        validate_action_fees(fees)?;
+    }
+    write_action_fees(&env, &action, &fees);
+    Ok(())
+}
+
+pub fn read_fee_schedule(env: &Env) -> Vec<FeeScheduleEntry> {
+    let mut schedule = Vec::new(env);
+    for action in FEE_ACTIONS {
+        let custom = read_action_fee_override(env, &action);
+        schedule.push_back(FeeScheduleEntry {
+            custom: custom.is_some(),
+            fees: custom.unwrap_or_else(|| read_action_fees(env, &action)),
+            action,
+        });
+    }
+    schedule
+}
+
+pub fn read_fee_claimable(env: &Env, recipient: &Address) -> UserClaimableBalance {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::FeeClaimable(recipient.clone()))
+        .unwrap_or(UserClaimableBalance {
+            terry: 0,
+            power: 0,
+            xtar: 0,
+            last_claim_round: 0,
+            last_claim_timestamp: 0,
+            rounds: Vec::new(env),
+        })
+}
+
+fn write_fee_claimable(env: &Env, recipient: &Address, claimable: &UserClaimableBalance) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::FeeClaimable(recipient.clone()), claimable);
+}
+
+pub fn read_fee_token_claimable(env: &Env, recipient: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::FeeTokenClaimable(recipient.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+fn write_fee_token_claimable(env: &Env, recipient: &Address, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::FeeTokenClaimable(recipient.clone(), token.clone()), &amount);
+}
+
+/// Splits every unreleased fee among the recipients. The last recipient takes
+/// the rounding remainder so the full amount is always assigned.
+pub fn release(env: &Env) {
+    release_tokens(env);
+    let fees = read_account(env, Account::DogstarFees);
+    if fees.terry == 0 && fees.power == 0 && fees.xtar == 0 {
+        return;
+    }
+    post_all(
+        env,
+        Account::DogstarFees,
+        Account::DogstarClaimable,
+        fees.terry,
+        fees.power as u32,
+        fees.xtar,
+        Action::None,
+    );
+
+    let recipients = read_fee_recipients(env);
+    let last = recipients.len() - 1;
+    let (mut terry_left, mut power_left, mut xtar_left) = (fees.terry, fees.power, fees.xtar);
+    for (i, recipient) in recipients.iter().enumerate() {
+        let (terry, power, xtar) = if i as u32 == last {
+            (terry_left, power_left, xtar_left)
+        } else {
+            let bps = recipient.bps as i128;
+            (
+                fees.terry * bps / 10000,
+                fees.power * bps / 10000,
+                fees.xtar * bps / 10000,
+            )
+        };
+        terry_left -= terry;
+        power_left -= power;
+        xtar_left -= xtar;
+
+        let mut claimable = read_fee_claimable(env, &recipient.address);
+        claimable.terry += terry;
+        claimable.power += power as u32;
+        claimable.xtar += xtar;
+        write_fee_claimable(env, &recipient.address, &claimable);
+        emit_fee_share_released(env, &recipient.address, terry, power as u32, xtar);
+    }
+}
+
+/// Pays a recipient its released fee share.
+pub fn claim(env: Env, recipient: Address) -> Result<(i128, u32, i128), NFTError> {
+    recipient.require_auth();
+
+    let mut claimable = read_fee_claimable(&env, &recipient);
+    if claimable.terry == 0 && claimable.power == 0 && claimable.xtar == 0 {
+        return Err(NFTError::NoRewardsAvailable);
+    }
+    let (terry, power, xtar) = (claimable.terry, claimable.power, claimable.xtar);
+
+    if power > 0 {
+        let mut user = read_user(&env, recipient.clone());
+        user.power += power;
+        write_user(&env, recipient.clone(), user);
+    }
+    // Through mint_terry so the share counts towards the recipient's history and level
+    if terry > 0 {
+        mint_terry(&env, recipient.clone(), terry);
+    }
+    if xtar > 0 {
+        let config = read_config(&env);
+        let token = token::Client::new(&env, &config.xtar_token);
+        token.transfer(&env.current_contract_address(), &recipient, &xtar);
+    }
+
+    let timestamp = env.ledger().timestamp();
+    let round = get_current_round(&env);
+    claimable.terry = 0;
+    claimable.power = 0;
+    claimable.xtar = 0;
+    claimable.last_claim_timestamp = timestamp;
+    claimable.last_claim_round = round;
+    write_fee_claimable(&env, &recipient, &claimable);
+
+    // Keep the aggregate claim record current for existing views
+    let mut aggregate = read_dogstar_claimable(&env);
+    aggregate.last_claim_timestamp = timestamp;
+    aggregate.last_claim_round = round;
+    write_dogstar_claimable(&env, &aggregate);
+
+    post_all(
+        &env,
+        Account::DogstarClaimable,
+        Account::External,
+        terry,
+        power,
+        xtar,
+        Action::None,
+    );
+    emit_dogstar_fee_withdrawn(&env, &recipient, terry, power, xtar);
+    Ok((terry, power, xtar))
+}
+
+/// Splits the unreleased fees taken in registered tokens the same way as `release`.
+fn release_tokens(env: &Env) {
+    let recipients = read_fee_recipients(env);
+    let last = recipients.len() - 1;
+    for token in read_pot_tokens(env).iter() {
+        let asset = Asset::Token(token.clone());
+        let fees = read_account_balance(env, Account::DogstarFees, asset.clone());
+        if fees == 0 {
+            continue;
+        }
+        post(env, asset, Account::DogstarFees, Account::DogstarClaimable, fees, Action::None);
+
+        let mut left = fees;
+        for (i, recipient) in recipients.iter().enumerate() {
+            let amount = if i as u32 == last {
+                left
+            } else {
+                fees * recipient.bps as i128 / 10000
+            };
+            left -= amount;
+            let claimable = read_fee_token_claimable(env, &recipient.address, &token);
+            write_fee_token_claimable(env, &recipient.address, &token, claimable + amount);
+            emit_fee_token_share_released(env, &recipient.address, &token, amount);
+        }
+    }
+}
+
+/// Pays a recipient its released fee share in a registered token.
+pub fn claim_token(env: Env, recipient: Address, token: Address) -> Result<i128, NFTError> {
+    recipient.require_auth();
+
+    let amount = read_fee_token_claimable(&env, &recipient, &token);
+    if amount == 0 {
+        return Err(NFTError::NoRewardsAvailable);
+    }
+    write_fee_token_claimable(&env, &recipient, &token, 0);
+    token::Client::new(&env, &token).transfer(&env.current_contract_address(), &recipient, &amount);
+    post(
+        &env,
+        Asset::Token(token.clone()),
+        Account::DogstarClaimable,
+        Account::External,
+        amount,
+        Action::None,
+    );
+    emit_fee_token_share_claimed(&env, &recipient, &token, amount);
+    Ok(amount)
+}
@@ -0,0 +1,3 @@
+pub mod proposal;
+pub mod timelock;
+pub mod weights;
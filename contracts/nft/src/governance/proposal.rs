@@ -0,0 +1,229 @@
+use crate::admin::{read_administrator, read_config, validate_config};
+use crate::error::{EconomyError, NFTError};
+use crate::event::{emit_proposal_created, emit_proposal_finalized, emit_vote_cast};
+use crate::governance::timelock::{apply_change, queue_change, validate_external_change, ConfigChange};
+use crate::governance::weights;
+use crate::pot::tokens::ensure_supported_policies;
+use crate::storage_types::DataKey;
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Vec};
+
+const DEFAULT_VOTING_PERIOD: u64 = 3 * 24 * 3600; // 3 days
+const DEFAULT_QUORUM_BPS: u32 = 1000; // 10% of the snapshotted weight
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GovernanceSettings {
+    pub voting_period: u64, // Seconds a proposal accepts votes
+    pub quorum_bps: u32,    // Share of the snapshotted weight that must vote, in basis points
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Queued,
+    Rejected,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u32,
+    pub proposer: Address,
+    pub change: ConfigChange,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub total_weight: u64, // Sum of all voting weight at creation
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub status: ProposalStatus,
+    pub queued_change_id: Option<u32>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Vote {
+    pub support: bool,
+    pub weight: u64,
+}
+
+pub fn read_governance_settings(env: &Env) -> GovernanceSettings {
+    env.storage()
+        .persistent()
+        .get(&DataKey::GovernanceSettings)
+        .unwrap_or(GovernanceSettings {
+            voting_period: DEFAULT_VOTING_PERIOD,
+            quorum_bps: DEFAULT_QUORUM_BPS,
+        })
+}
+
+pub fn set_governance_settings(env: Env, settings: GovernanceSettings) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+    if settings.voting_period == 0 || settings.quorum_bps > 10000 {
+        return Err(NFTError::InvalidGovernanceSettings);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey::GovernanceSettings, &settings);
+    Ok(())
+}
+
+pub fn is_proposer(env: &Env, member: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Proposer(member.clone()))
+        .unwrap_or(false)
+}
+
+pub fn set_proposer(env: Env, member: Address, approved: bool) {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+    let key = DataKey::Proposer(member);
+    if approved {
+        env.storage().persistent().set(&key, &true);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+}
+
+pub fn read_proposal(env: &Env, id: u32) -> Option<Proposal> {
+    env.storage().persistent().get(&DataKey::Proposal(id))
+}
+
+fn write_proposal(env: &Env, proposal: &Proposal) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::Proposal(proposal.id), proposal);
+}
+
+/// Number of proposals created so far, which is also the id of the last one.
+pub fn read_proposal_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::ProposalCounter)
+        .unwrap_or(0)
+}
+
+pub fn read_proposals(env: &Env) -> Vec<Proposal> {
+    let mut proposals = Vec::new(env);
+    for id in 1..=read_proposal_count(env) {
+        if let Some(proposal) = read_proposal(env, id) {
+            proposals.push_back(proposal);
+        }
+    }
+    proposals
+}
+
+/// Weight `voter` holds on proposal `id`, as of the proposal's creation.
+pub fn read_vote_weight(env: &Env, id: u32, voter: &Address) -> u64 {
+    weights::weight_at(env, voter, id)
+}
+
+pub fn read_vote(env: &Env, id: u32, voter: &Address) -> Option<Vote> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::Vote(id, voter.clone()))
+}
+
+pub fn create_proposal(env: Env, proposer: Address, change: ConfigChange) -> Result<u32, NFTError> {
+    proposer.require_auth();
+    if !is_proposer(&env, &proposer) {
+        return Err(NFTError::NotProposer);
+    }
+
+    // Reject proposals that could never be applied
+    let mut config = read_config(&env);
+    apply_change(&mut config, &change);
+    validate_config(&config)?;
+    ensure_supported_policies(&env, &config);
+    validate_external_change(&env, &change)?;
+
+    // Weights are only known for voters once every deck and stake is tracked
+    if !weights::is_seeded(&env) {
+        panic_with_error!(&env, EconomyError::MigrationPending);
+    }
+
+    let id = read_proposal_count(&env) + 1;
+    env.storage().persistent().set(&DataKey::ProposalCounter, &id);
+
+    let settings = read_governance_settings(&env);
+    let now = env.ledger().timestamp();
+    let proposal = Proposal {
+        id,
+        proposer,
+        change,
+        start_time: now,
+        end_time: now + settings.voting_period,
+        total_weight: weights::read_total_weight(&env),
+        votes_for: 0,
+        votes_against: 0,
+        status: ProposalStatus::Active,
+        queued_change_id: None,
+    };
+    write_proposal(&env, &proposal);
+
+    emit_proposal_created(&env, &proposal);
+    Ok(id)
+}
+
+pub fn vote(env: Env, voter: Address, id: u32, support: bool) -> Result<(), NFTError> {
+    voter.require_auth();
+    let mut proposal = read_proposal(&env, id).ok_or(NFTError::ProposalNotFound)?;
+    if proposal.status != ProposalStatus::Active || env.ledger().timestamp() >= proposal.end_time {
+        return Err(NFTError::VotingClosed);
+    }
+    if read_vote(&env, id, &voter).is_some() {
+        return Err(NFTError::AlreadyVoted);
+    }
+
+    let weight = read_vote_weight(&env, id, &voter);
+    if weight == 0 {
+        return Err(NFTError::NoVotingPower);
+    }
+
+    if support {
+        proposal.votes_for += weight;
+    } else {
+        proposal.votes_against += weight;
+    }
+    write_proposal(&env, &proposal);
+    env.storage()
+        .persistent()
+        .set(&DataKey::Vote(id, voter.clone()), &Vote { support, weight });
+
+    emit_vote_cast(&env, &voter, id, support, weight);
+    Ok(())
+}
+
+/// Closes voting once the window has passed. Passing proposals are queued in the
+/// timelock with the minimum delay. Permissionless.
+pub fn finalize_proposal(env: Env, id: u32) -> Result<ProposalStatus, NFTError> {
+    let mut proposal = read_proposal(&env, id).ok_or(NFTError::ProposalNotFound)?;
+    if proposal.status != ProposalStatus::Active {
+        return Err(NFTError::ProposalNotActive);
+    }
+    let now = env.ledger().timestamp();
+    if now < proposal.end_time {
+        return Err(NFTError::VotingOpen);
+    }
+
+    let settings = read_governance_settings(&env);
+    let turnout = proposal.votes_for + proposal.votes_against;
+    let quorum = (proposal.total_weight as u128 * settings.quorum_bps as u128 / 10000) as u64;
+    let passed = turnout > 0 && turnout >= quorum && proposal.votes_for > proposal.votes_against;
+
+    proposal.status = ProposalStatus::Rejected;
+    if passed {
+        let delay = read_config(&env).param_change_delay;
+        // A change made invalid by later config updates is rejected rather than left stuck
+        if let Ok(change_id) = queue_change(&env, proposal.change.clone(), now + delay, true) {
+            proposal.status = ProposalStatus::Queued;
+            proposal.queued_change_id = Some(change_id);
+        }
+    }
+    write_proposal(&env, &proposal);
+
+    emit_proposal_finalized(&env, &proposal);
+    Ok(proposal.status)
+}
@@ -0,0 +1,291 @@
+use crate::admin::{read_administrator, read_config, validate_config, write_config};
+use crate::error::NFTError;
+use crate::event::{emit_change_cancelled, emit_change_executed, emit_change_scheduled};
+use crate::curve::{validate_curve, write_curve, PriceCurve};
+use crate::fees::{
+    validate_action_fees, validate_fee_recipients, write_action_fees, write_fee_recipients, ActionFees,
+    FeeRecipient,
+};
+use crate::nft_info::Action;
+use crate::pot::modes::{validate_distribution_mode, write_distribution_mode, DistributionMode};
+use crate::pot::tokens::ensure_supported_policies;
+use crate::pricing::{validate_usd_price, write_usd_price};
+use crate::sale::{validate_sale, write_sale, SaleConfig};
+use crate::storage_types::{Config, DataKey, DataKey2};
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// An economic parameter change that goes through the timelock queue.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigChange {
+    XtarToken(Address),
+    OracleContract(Address),
+    WithdrawablePercentage(u32),
+    BurnablePercentage(u32),
+    HawAiPercentage(u32),
+    BurnReceivePercentage(u32),
+    PowerActionFee(u32),
+    DogstarFeePercentage(u32),
+    ApyAlpha(u32),
+    TerryPerPower(i128),
+    TerryPerDeck(i128),
+    TerryPerFight(i128),
+    TerryPerLending(i128),
+    TerryPerStake(i128),
+    PowerToUsdcRate(i128),
+    StakeSchedule(Vec<u32>, Vec<u32>),
+    ParamChangeDelay(u64),
+    RoundDuration(u64),
+    KeeperRewardBps(u32),
+    PotRolloverBps(u32),
+    JackpotReserveBps(u32),
+    JackpotInterval(u32),
+    ClaimExpiryRounds(u32),
+    VestingPeriod(u64),
+    EarlyClaimPenaltyBps(u32),
+    /// Sets the fee schedule of one action.
+    ActionFees(Action, ActionFees),
+    /// Returns an action to the global config fees.
+    ClearActionFees(Action),
+    /// Sets how the opening of a future round pays out.
+    DistributionMode(u32, DistributionMode),
+    /// Replaces the fee split.
+    FeeRecipients(Vec<FeeRecipient>),
+    /// Sets a template's USD price.
+    UsdPrice(u32, i128),
+    /// Clears a template's USD price.
+    ClearUsdPrice(u32),
+    /// Sets a template's price curve.
+    PriceCurve(u32, PriceCurve),
+    /// Removes a template's price curve.
+    ClearPriceCurve(u32),
+    /// Sets a template's sale schedule.
+    Sale(u32, SaleConfig),
+    /// Removes a template's sale schedule.
+    ClearSale(u32),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub id: u32,
+    pub change: ConfigChange,
+    pub eta: u64,
+    pub queued_at: u64,
+}
+
+pub fn apply_change(config: &mut Config, change: &ConfigChange) {
+    match change.clone() {
+        ConfigChange::XtarToken(v) => config.xtar_token = v,
+        ConfigChange::OracleContract(v) => config.oracle_contract_id = v,
+        ConfigChange::WithdrawablePercentage(v) => config.withdrawable_percentage = v,
+        ConfigChange::BurnablePercentage(v) => config.burnable_percentage = v,
+        ConfigChange::HawAiPercentage(v) => config.haw_ai_percentage = v,
+        ConfigChange::BurnReceivePercentage(v) => config.burn_receive_percentage = v,
+        ConfigChange::PowerActionFee(v) => config.power_action_fee = v,
+        ConfigChange::DogstarFeePercentage(v) => config.dogstar_fee_percentage = v,
+        ConfigChange::ApyAlpha(v) => config.apy_alpha = v,
+        ConfigChange::TerryPerPower(v) => config.terry_per_power = v,
+        ConfigChange::TerryPerDeck(v) => config.terry_per_deck = v,
+        ConfigChange::TerryPerFight(v) => config.terry_per_fight = v,
+        ConfigChange::TerryPerLending(v) => config.terry_per_lending = v,
+        ConfigChange::TerryPerStake(v) => config.terry_per_stake = v,
+        ConfigChange::PowerToUsdcRate(v) => config.power_to_usdc_rate = v,
+        ConfigChange::StakeSchedule(periods, percentages) => {
+            config.stake_periods = periods;
+            config.stake_interest_percentages = percentages;
+        }
+        ConfigChange::ParamChangeDelay(v) => config.param_change_delay = v,
+        ConfigChange::RoundDuration(v) => config.round_duration = v,
+        ConfigChange::KeeperRewardBps(v) => config.keeper_reward_bps = v,
+        ConfigChange::PotRolloverBps(v) => config.pot_rollover_bps = v,
+        ConfigChange::JackpotReserveBps(v) => config.jackpot_reserve_bps = v,
+        ConfigChange::JackpotInterval(v) => config.jackpot_interval = v,
+        ConfigChange::ClaimExpiryRounds(v) => config.claim_expiry_rounds = v,
+        ConfigChange::VestingPeriod(v) => config.vesting_period = v,
+        ConfigChange::EarlyClaimPenaltyBps(v) => config.early_claim_penalty_bps = v,
+        // Stored outside the config, see `apply_external_change`
+        ConfigChange::ActionFees(_, _)
+        | ConfigChange::ClearActionFees(_)
+        | ConfigChange::DistributionMode(_, _)
+        | ConfigChange::FeeRecipients(_)
+        | ConfigChange::UsdPrice(_, _)
+        | ConfigChange::ClearUsdPrice(_)
+        | ConfigChange::PriceCurve(_, _)
+        | ConfigChange::ClearPriceCurve(_)
+        | ConfigChange::Sale(_, _)
+        | ConfigChange::ClearSale(_) => {}
+    }
+}
+
+/// Validates the part of a change that is not stored in `Config`.
+pub fn validate_external_change(env: &Env, change: &ConfigChange) -> Result<(), NFTError> {
+    match change {
+        ConfigChange::ActionFees(_, fees) => validate_action_fees(fees),
+        ConfigChange::DistributionMode(round, mode) => validate_distribution_mode(env, *round, mode),
+        ConfigChange::FeeRecipients(recipients) => validate_fee_recipients(recipients),
+        ConfigChange::UsdPrice(token_id, price) => validate_usd_price(env, *token_id, Some(*price)),
+        ConfigChange::ClearUsdPrice(token_id) => validate_usd_price(env, *token_id, None),
+        ConfigChange::PriceCurve(token_id, curve) => validate_curve(env, *token_id, &Some(curve.clone())),
+        ConfigChange::ClearPriceCurve(token_id) => validate_curve(env, *token_id, &None),
+        ConfigChange::Sale(token_id, sale) => validate_sale(env, *token_id, &Some(sale.clone())),
+        ConfigChange::ClearSale(token_id) => validate_sale(env, *token_id, &None),
+        _ => Ok(()),
+    }
+}
+
+fn apply_external_change(env: &Env, change: &ConfigChange) {
+    match change {
+        ConfigChange::ActionFees(action, fees) => write_action_fees(env, action, &Some(fees.clone())),
+        ConfigChange::ClearActionFees(action) => write_action_fees(env, action, &None),
+        ConfigChange::DistributionMode(round, mode) => write_distribution_mode(env, *round, mode),
+        ConfigChange::FeeRecipients(recipients) => write_fee_recipients(env, recipients),
+        ConfigChange::UsdPrice(token_id, price) => write_usd_price(env, *token_id, Some(*price)),
+        ConfigChange::ClearUsdPrice(token_id) => write_usd_price(env, *token_id, None),
+        ConfigChange::PriceCurve(token_id, curve) => write_curve(env, *token_id, &Some(curve.clone())),
+        ConfigChange::ClearPriceCurve(token_id) => write_curve(env, *token_id, &None),
+        ConfigChange::Sale(token_id, sale) => write_sale(env, *token_id, &Some(sale.clone())),
+        ConfigChange::ClearSale(token_id) => write_sale(env, *token_id, &None),
+        _ => {}
+    }
+}
+
+/// Direct (instant) setters are only allowed while no timelock delay is configured.
+pub fn ensure_no_timelock(config: &Config) -> Result<(), NFTError> {
+    if config.param_change_delay > 0 {
+        return Err(NFTError::TimelockRequired);
+    }
+    Ok(())
+}
+
+pub fn read_queued_change_ids(env: &Env) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::QueuedChangeIds)
+        .unwrap_or(Vec::new(env))
+}
+
+fn write_queued_change_ids(env: &Env, ids: &Vec<u32>) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::QueuedChangeIds, ids);
+}
+
+pub fn read_queued_change(env: &Env, id: u32) -> Option<QueuedChange> {
+    env.storage()
+        .persistent()
+        .get(&DataKey::QueuedChange(id))
+}
+
+fn remove_queued_change(env: &Env, id: u32) {
+    env.storage().persistent().remove(&DataKey::QueuedChange(id));
+    env.storage().persistent().remove(&DataKey2::GovernanceChange(id));
+
+    let mut ids = read_queued_change_ids(env);
+    if let Some(pos) = ids.iter().position(|x| x == id) {
+        ids.remove(pos as u32);
+    }
+    write_queued_change_ids(env, &ids);
+}
+
+fn next_change_id(env: &Env) -> u32 {
+    let prev = env
+        .storage()
+        .persistent()
+        .get(&DataKey::QueuedChangeCounter)
+        .unwrap_or(0u32);
+    env.storage()
+        .persistent()
+        .set(&DataKey::QueuedChangeCounter, &(prev + 1));
+    prev + 1
+}
+
+/// Queues a change without checking authorization. Used by the admin entrypoint
+/// and, with `by_governance` set, by passed governance proposals.
+pub fn queue_change(env: &Env, change: ConfigChange, eta: u64, by_governance: bool) -> Result<u32, NFTError> {
+    let mut config = read_config(env);
+    let now = env.ledger().timestamp();
+    if eta < now.saturating_add(config.param_change_delay) {
+        return Err(NFTError::TimelockTooShort);
+    }
+    // Reject changes that could never be applied to the current config
+    apply_change(&mut config, &change);
+    validate_config(&config)?;
+    ensure_supported_policies(env, &config);
+    validate_external_change(env, &change)?;
+
+    let id = next_change_id(env);
+    let queued = QueuedChange {
+        id,
+        change,
+        eta,
+        queued_at: now,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey::QueuedChange(id), &queued);
+    if by_governance {
+        env.storage()
+            .persistent()
+            .set(&DataKey2::GovernanceChange(id), &true);
+    }
+
+    let mut ids = read_queued_change_ids(env);
+    ids.push_back(id);
+    write_queued_change_ids(env, &ids);
+
+    emit_change_scheduled(env, &queued);
+    Ok(id)
+}
+
+pub fn schedule_change(env: Env, change: ConfigChange, eta: u64) -> Result<u32, NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+    queue_change(&env, change, eta, false)
+}
+
+/// Applies a queued change once its eta has passed. Permissionless.
+pub fn execute_change(env: Env, id: u32) -> Result<(), NFTError> {
+    let queued = read_queued_change(&env, id).ok_or(NFTError::ChangeNotFound)?;
+    if env.ledger().timestamp() < queued.eta {
+        return Err(NFTError::TimelockNotReady);
+    }
+
+    // Re-validate against the config as it is now, other changes may have landed
+    let mut config = read_config(&env);
+    apply_change(&mut config, &queued.change);
+    validate_config(&config)?;
+    ensure_supported_policies(&env, &config);
+    validate_external_change(&env, &queued.change)?;
+    write_config(&env, &config);
+    apply_external_change(&env, &queued.change);
+
+    remove_queued_change(&env, id);
+    emit_change_executed(&env, &queued);
+    Ok(())
+}
+
+/// Drops a change the admin queued. Changes queued by passed proposals can only
+/// be executed, so the admin cannot veto a governance vote.
+pub fn cancel_change(env: Env, id: u32) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    let queued = read_queued_change(&env, id).ok_or(NFTError::ChangeNotFound)?;
+    if env.storage().persistent().has(&DataKey2::GovernanceChange(id)) {
+        return Err(NFTError::NotAuthorized);
+    }
+    remove_queued_change(&env, id);
+    emit_change_cancelled(&env, &queued);
+    Ok(())
+}
+
+pub fn read_pending_changes(env: &Env) -> Vec<QueuedChange> {
+    let mut changes = Vec::new(env);
+    for id in read_queued_change_ids(env).iter() {
+        if let Some(queued) = read_queued_change(env, id) {
+            changes.push_back(queued);
+        }
+    }
+    changes
+}
@@ -0,0 +1,162 @@
+use crate::actions::{deck::read_decks, stake::read_stakes};
+use crate::governance::proposal::read_proposal_count;
+use crate::pot::management::calculate_effective_power;
+use crate::storage_types::DataKey2;
+use soroban_sdk::{contracttype, Address, Env, Map};
+
+/// A player's voting weight from proposal epoch `epoch` on. The epoch is the
+/// number of proposals created when the weight changed, so proposal `id` sees
+/// the last checkpoint with an epoch below `id`, and cards moved after it was
+/// created cannot vote twice.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteCheckpoint {
+    pub epoch: u32,
+    /// Effective power of a complete deck.
+    pub deck: u64,
+    /// POWER locked in stakes.
+    pub staked: u64,
+}
+
+impl VoteCheckpoint {
+    fn weight(&self) -> u64 {
+        self.deck + self.staked
+    }
+}
+
+fn read_checkpoint_count(env: &Env, player: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::VoteCheckpoints(player.clone()))
+        .unwrap_or(0)
+}
+
+fn read_checkpoint(env: &Env, player: &Address, index: u32) -> VoteCheckpoint {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::VoteCheckpoint(player.clone(), index))
+        .unwrap_or(VoteCheckpoint {
+            epoch: 0,
+            deck: 0,
+            staked: 0,
+        })
+}
+
+fn latest(env: &Env, player: &Address) -> VoteCheckpoint {
+    match read_checkpoint_count(env, player) {
+        0 => read_checkpoint(env, player, 0),
+        count => read_checkpoint(env, player, count - 1),
+    }
+}
+
+/// Sum of every player's current voting weight.
+pub fn read_total_weight(env: &Env) -> u64 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TotalVoteWeight)
+        .unwrap_or(0)
+}
+
+/// Whether weights are tracked; false on contracts that predate governance until
+/// `seed` has run.
+pub fn is_seeded(env: &Env) -> bool {
+    env.storage().persistent().has(&DataKey2::TotalVoteWeight)
+}
+
+/// Records the weight of `player` for the current epoch, replacing a checkpoint
+/// already taken in it, and moves the total by the difference.
+fn record(env: &Env, player: &Address, deck: u64, staked: u64) {
+    let count = read_checkpoint_count(env, player);
+    let last = latest(env, player);
+    let epoch = read_proposal_count(env);
+    let checkpoint = VoteCheckpoint { epoch, deck, staked };
+
+    let index = if count > 0 && last.epoch == epoch {
+        count - 1
+    } else {
+        env.storage()
+            .persistent()
+            .set(&DataKey2::VoteCheckpoints(player.clone()), &(count + 1));
+        count
+    };
+    let total = read_total_weight(env) - last.weight() + checkpoint.weight();
+    env.storage()
+        .persistent()
+        .set(&DataKey2::VoteCheckpoint(player.clone(), index), &checkpoint);
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TotalVoteWeight, &total);
+}
+
+/// Tracks the effective power of the deck of `player`; zero while incomplete.
+pub fn set_deck_weight(env: &Env, player: &Address, weight: u32) {
+    if !is_seeded(env) {
+        return;
+    }
+    let last = latest(env, player);
+    if last.deck != weight as u64 {
+        record(env, player, weight as u64, last.staked);
+    }
+}
+
+/// Tracks a stake of `player` changing from `old` to `new` POWER.
+pub fn adjust_staked(env: &Env, player: &Address, old: u32, new: u32) {
+    if !is_seeded(env) || old == new {
+        return;
+    }
+    let last = latest(env, player);
+    record(env, player, last.deck, last.staked - old as u64 + new as u64);
+}
+
+/// Weight of `player` on proposal `id`: its last checkpoint from before the
+/// proposal was created, found by binary search.
+pub fn weight_at(env: &Env, player: &Address, id: u32) -> u64 {
+    let (mut low, mut high) = (0, read_checkpoint_count(env, player));
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if read_checkpoint(env, player, mid).epoch < id {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    match low {
+        0 => 0,
+        index => read_checkpoint(env, player, index - 1).weight(),
+    }
+}
+
+/// Takes the first checkpoint of every deck and stake owner on contracts that
+/// predate governance. Returns false when weights are already tracked.
+pub fn seed(env: &Env) -> bool {
+    if is_seeded(env) {
+        return false;
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TotalVoteWeight, &0u64);
+
+    let mut staked: Map<Address, u64> = Map::new(env);
+    for stake in read_stakes(env.clone()).iter() {
+        let current = staked.get(stake.owner.clone()).unwrap_or(0);
+        staked.set(stake.owner, current + stake.power as u64);
+    }
+    for deck in read_decks(env.clone()).iter() {
+        let weight = if deck.token_ids.len() == 4 {
+            calculate_effective_power(deck.total_power, deck.bonus) as u64
+        } else {
+            0
+        };
+        let player_staked = staked.get(deck.owner.clone()).unwrap_or(0);
+        staked.remove(deck.owner.clone());
+        if weight > 0 || player_staked > 0 {
+            record(env, &deck.owner, weight, player_staked);
+        }
+    }
+    for (owner, player_staked) in staked.iter() {
+        if player_staked > 0 {
+            record(env, &owner, 0, player_staked);
+        }
+    }
+    true
+}
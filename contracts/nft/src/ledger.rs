@@ -0,0 +1,302 @@
+use crate::error::EconomyError;
+use crate::nft_info::Action;
+use crate::pot::management::get_current_round;
+use crate::storage_types::{
+    Balance, ContractVault, DataKey, DataKey2, LegacyClaimableBalance, PotBalance,
+    UserClaimableBalance, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD,
+};
+use soroban_sdk::{contracttype, panic_with_error, vec, Address, Env, Vec};
+
+/// Most journal entries a single `journal` call may return.
+pub const MAX_JOURNAL_PAGE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Terry,
+    Power,
+    Xtar,
+    /// A registered payment or sponsor token other than XTAR.
+    Token(Address),
+}
+
+/// Named accounts of the protocol books. `External` is the counterparty for
+/// everything entering or leaving the contract, so it carries the negative of
+/// all other balances.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Account {
+    External,
+    /// Accumulated since the last opening, waiting to be distributed.
+    Pot,
+    /// Action fees and reward shares kept by the protocol outside the pot.
+    HawAi,
+    /// Dogstar fees not yet released.
+    DogstarFees,
+    /// Dogstar fees released and waiting to be claimed.
+    DogstarClaimable,
+    /// Pot shares assigned to players but not yet claimed.
+    Claimable,
+    /// Admin share of TERRY mint payments.
+    AdminWithdrawable,
+    /// Jackpot reserve set aside from the pot over several rounds.
+    Jackpot,
+}
+
+pub const ACCOUNTS: [Account; 8] = [
+    Account::External,
+    Account::Pot,
+    Account::HawAi,
+    Account::DogstarFees,
+    Account::DogstarClaimable,
+    Account::Claimable,
+    Account::AdminWithdrawable,
+    Account::Jackpot,
+];
+
+/// A single balanced movement: `amount` of `asset` leaves `from` and enters `to`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct JournalEntry {
+    pub id: u32,
+    pub round: u32,
+    pub asset: Asset,
+    pub from: Account,
+    pub to: Account,
+    pub amount: i128,
+    pub action: Action,
+    pub timestamp: u64,
+}
+
+/// Ids of the journal entries posted while a round was the latest opened one.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JournalRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountBalance {
+    pub account: Account,
+    pub terry: i128,
+    pub power: i128,
+    pub xtar: i128,
+}
+
+pub fn read_account_balance(env: &Env, account: Account, asset: Asset) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::LedgerBalance(account, asset))
+        .unwrap_or(0)
+}
+
+fn write_account_balance(env: &Env, account: Account, asset: Asset, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey::LedgerBalance(account, asset), &amount);
+}
+
+pub fn read_account(env: &Env, account: Account) -> AccountBalance {
+    AccountBalance {
+        account,
+        terry: read_account_balance(env, account, Asset::Terry),
+        power: read_account_balance(env, account, Asset::Power),
+        xtar: read_account_balance(env, account, Asset::Xtar),
+    }
+}
+
+pub fn read_accounts(env: &Env) -> Vec<AccountBalance> {
+    let mut accounts = vec![env];
+    for account in ACCOUNTS {
+        accounts.push_back(read_account(env, account));
+    }
+    accounts
+}
+
+pub fn read_journal_range(env: &Env, round: u32) -> Option<JournalRange> {
+    env.storage().persistent().get(&DataKey2::JournalRange(round))
+}
+
+/// Up to `limit` (at most MAX_JOURNAL_PAGE) of the entries filed under `round`,
+/// skipping the first `start`, oldest first. Entries are filed under the latest
+/// opened round (0 before the first opening).
+pub fn read_journal(env: &Env, round: u32, start: u32, limit: u32) -> Vec<JournalEntry> {
+    let mut entries = vec![env];
+    let Some(range) = read_journal_range(env, round) else {
+        return entries;
+    };
+    let first = range.first.saturating_add(start);
+    let end = range
+        .last
+        .saturating_add(1)
+        .min(first.saturating_add(limit.min(MAX_JOURNAL_PAGE)));
+    for id in first..end {
+        if let Some(entry) = env.storage().persistent().get(&DataKey::Journal(id)) {
+            entries.push_back(entry);
+        }
+    }
+    entries
+}
+
+/// Records a movement between two accounts. Zero amounts are not recorded.
+/// Fails with `MigrationPending` until `migrate_legacy_balances` has run on
+/// contracts that predate the ledger.
+pub fn post(env: &Env, asset: Asset, from: Account, to: Account, amount: i128, action: Action) {
+    if amount == 0 || from == to {
+        return;
+    }
+    if env.storage().persistent().has(&DataKey2::ContractVault) {
+        panic_with_error!(env, EconomyError::MigrationPending);
+    }
+    let from_balance = read_account_balance(env, from, asset.clone());
+    write_account_balance(env, from, asset.clone(), from_balance - amount);
+    let to_balance = read_account_balance(env, to, asset.clone());
+    write_account_balance(env, to, asset.clone(), to_balance + amount);
+
+    let id = env
+        .storage()
+        .persistent()
+        .get::<_, u32>(&DataKey::JournalCounter)
+        .unwrap_or(0)
+        + 1;
+    env.storage().persistent().set(&DataKey::JournalCounter, &id);
+
+    // Rounds only move forward, so each round's entries have consecutive ids
+    let round = get_current_round(env);
+    let range = match read_journal_range(env, round) {
+        Some(range) => JournalRange { last: id, ..range },
+        None => JournalRange { first: id, last: id },
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey2::JournalRange(round), &range);
+
+    let key = DataKey::Journal(id);
+    env.storage().persistent().set(
+        &key,
+        &JournalEntry {
+            id,
+            round,
+            asset,
+            from,
+            to,
+            amount,
+            action,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+}
+
+/// Posts the same movement for each non-zero asset amount.
+pub fn post_all(
+    env: &Env,
+    from: Account,
+    to: Account,
+    terry: i128,
+    power: u32,
+    xtar: i128,
+    action: Action,
+) {
+    post(env, Asset::Terry, from, to, terry, action.clone());
+    post(env, Asset::Power, from, to, power as i128, action.clone());
+    post(env, Asset::Xtar, from, to, xtar, action);
+}
+
+/// Seeds the accounts from the vault and balance records kept before the ledger
+/// existed, then removes the records the ledger replaces. Returns false when
+/// there is nothing to migrate, e.g. on contracts deployed with the ledger.
+pub fn migrate_legacy_balances(env: &Env) -> bool {
+    let storage = env.storage().persistent();
+    let Some(vault) = storage.get::<_, ContractVault>(&DataKey2::ContractVault) else {
+        return false;
+    };
+    storage.remove(&DataKey2::ContractVault);
+    storage.remove(&DataKey2::DogstarBalance);
+
+    if let Some(pot) = storage.get::<_, PotBalance>(&DataKey::PotBalance) {
+        post_all(
+            env,
+            Account::External,
+            Account::Pot,
+            pot.accumulated_terry,
+            pot.accumulated_power,
+            pot.accumulated_xtar,
+            Action::None,
+        );
+    }
+    if let Some(balance) = storage.get::<_, Balance>(&DataKey::Balance) {
+        post_all(
+            env,
+            Account::External,
+            Account::AdminWithdrawable,
+            balance.admin_terry,
+            balance.admin_power,
+            0,
+            Action::None,
+        );
+        post_all(
+            env,
+            Account::External,
+            Account::HawAi,
+            balance.haw_ai_terry,
+            balance.haw_ai_power,
+            balance.haw_ai_xtar,
+            Action::None,
+        );
+    }
+    post_all(
+        env,
+        Account::External,
+        Account::Claimable,
+        vault.total_claimable_terry,
+        vault.total_claimable_power,
+        vault.total_claimable_xtar,
+        Action::None,
+    );
+
+    // The vault's Dogstar amounts include fees released but not yet claimed
+    let released = storage
+        .get::<_, LegacyClaimableBalance>(&DataKey::DogstarClaimableBalance)
+        .unwrap_or(LegacyClaimableBalance {
+            terry: 0,
+            power: 0,
+            xtar: 0,
+            last_claim_round: 0,
+            last_claim_timestamp: 0,
+        });
+    post_all(
+        env,
+        Account::External,
+        Account::DogstarClaimable,
+        released.terry,
+        released.power,
+        released.xtar,
+        Action::None,
+    );
+    post_all(
+        env,
+        Account::External,
+        Account::DogstarFees,
+        (vault.dogstar_terry - released.terry).max(0),
+        vault.dogstar_power.saturating_sub(released.power),
+        (vault.dogstar_xtar - released.xtar).max(0),
+        Action::None,
+    );
+    storage.set(
+        &DataKey::DogstarClaimableBalance,
+        &UserClaimableBalance {
+            terry: 0,
+            power: 0,
+            xtar: 0,
+            last_claim_round: released.last_claim_round,
+            last_claim_timestamp: released.last_claim_timestamp,
+            rounds: vec![env],
+        },
+    );
+    true
+}
@@ -3,12 +3,19 @@
 mod actions;
 mod admin;
 mod contract;
+mod curve;
 mod error;
 mod event;
+mod fees;
+mod governance;
+mod ledger;
 mod metadata;
 mod nft_info;
 mod pot;
+mod pricing;
+mod sale;
 mod storage_types;
+mod treasury;
 mod user_info;
 
 mod test;
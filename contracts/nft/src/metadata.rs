@@ -24,6 +24,10 @@ pub fn write_metadata(e: &Env, token_id : u32,  metadata: CardMetadata)  {
 }
 */
 
+pub fn has_metadata(e: &Env, token_id: u32) -> bool {
+    e.storage().instance().has(&DataKey::TokenId(token_id))
+}
+
 pub fn read_metadata(e: &Env, token_id: u32) -> CardMetadata {
     let key = DataKey::TokenId(token_id);
     e.storage().instance().get(&key).unwrap()
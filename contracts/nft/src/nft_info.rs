@@ -11,14 +11,16 @@ pub enum Category {
     Weapon,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 #[contracttype]
 pub enum Currency {
     Terry,
     Xtar,
+    /// A registered payment token, charged the template's USD price at the oracle rate.
+    Token(Address),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 #[contracttype]
 pub enum Action {
     None,
@@ -0,0 +1,82 @@
+use crate::admin::read_config;
+use crate::ledger::{read_account_balance, Account, Asset};
+use soroban_sdk::{contracttype, token, Address, Env};
+
+/// XTAR held by the contract against what it owes. TERRY and POWER are internal
+/// ledgers with no backing token, so only XTAR and the registered tokens (see
+/// `TokenSolvencyReport`) can be reconciled on-chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolvencyReport {
+    /// Actual XTAR balance of the contract.
+    pub xtar_balance: i128,
+    /// Pot XTAR accumulated since the last opening.
+    pub pot_xtar: i128,
+    /// XTAR assigned to players but not yet claimed.
+    pub claimable_xtar: i128,
+    /// Dogstar fees not yet released.
+    pub dogstar_unreleased_xtar: i128,
+    /// Dogstar fees released and waiting to be claimed.
+    pub dogstar_claimable_xtar: i128,
+    /// Jackpot reserve set aside for a later round.
+    pub jackpot_xtar: i128,
+    pub total_liabilities: i128,
+    /// Balance minus liabilities; negative means a deficit.
+    pub surplus: i128,
+    pub solvent: bool,
+}
+
+/// A registered token held by the contract against what the books owe in it:
+/// its pot, unclaimed player shares and Dogstar fees.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenSolvencyReport {
+    pub token: Address,
+    /// Actual balance of the contract.
+    pub balance: i128,
+    pub total_liabilities: i128,
+    /// Balance minus liabilities; negative means a deficit.
+    pub surplus: i128,
+    pub solvent: bool,
+}
+
+pub fn audit_token(env: &Env, token: &Address) -> TokenSolvencyReport {
+    let balance = token::Client::new(env, token).balance(&env.current_contract_address());
+    let total_liabilities = -read_account_balance(env, Account::External, Asset::Token(token.clone()));
+    let surplus = balance.saturating_sub(total_liabilities);
+    TokenSolvencyReport {
+        token: token.clone(),
+        balance,
+        total_liabilities,
+        surplus,
+        solvent: surplus >= 0,
+    }
+}
+
+pub fn audit(env: &Env) -> SolvencyReport {
+    let config = read_config(env);
+    let xtar_balance = token::Client::new(env, &config.xtar_token)
+        .balance(&env.current_contract_address());
+
+    let pot_xtar = read_account_balance(env, Account::Pot, Asset::Xtar);
+    let claimable_xtar = read_account_balance(env, Account::Claimable, Asset::Xtar);
+    let dogstar_unreleased_xtar = read_account_balance(env, Account::DogstarFees, Asset::Xtar);
+    let dogstar_claimable_xtar = read_account_balance(env, Account::DogstarClaimable, Asset::Xtar);
+    let jackpot_xtar = read_account_balance(env, Account::Jackpot, Asset::Xtar);
+
+    // Everything the books hold on the contract side is owed to someone
+    let total_liabilities = -read_account_balance(env, Account::External, Asset::Xtar);
+    let surplus = xtar_balance.saturating_sub(total_liabilities);
+
+    SolvencyReport {
+        xtar_balance,
+        pot_xtar,
+        claimable_xtar,
+        dogstar_unreleased_xtar,
+        dogstar_claimable_xtar,
+        jackpot_xtar,
+        total_liabilities,
+        surplus,
+        solvent: surplus >= 0,
+    }
+}
@@ -0,0 +1,58 @@
+use crate::actions::{deck, stake};
+use crate::error::NFTError;
+use crate::event::emit_power_compounded;
+use crate::nft_info::{read_nft, Action, Category};
+use crate::storage_types::{DataKey2, TokenId};
+use soroban_sdk::{contracttype, Address, Env};
+
+/// Where a player's claimed pot POWER goes. Whatever does not fit goes to
+/// `User.power`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub enum AutoCompound {
+    /// Straight to `User.power`.
+    Off,
+    /// Onto the deck cards in proportion to their power, up to their `max_power`.
+    Deck,
+    /// Into this stake, up to the card's `max_power`, without the fee and TERRY
+    /// reward of `increase_stake_power`. The stake period restarts.
+    Stake(Category, TokenId),
+}
+
+pub fn read_auto_compound(env: &Env, player: &Address) -> AutoCompound {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::CompoundPreference(player.clone()))
+        .unwrap_or(AutoCompound::Off)
+}
+
+/// Sets where the player's claimed pot POWER goes. A stake must be active
+/// (`StakeNotFound`).
+pub fn set_auto_compound(env: &Env, player: &Address, preference: &AutoCompound) -> Result<(), NFTError> {
+    if let AutoCompound::Stake(_, token_id) = preference {
+        let card = read_nft(env, player.clone(), token_id.clone()).ok_or(NFTError::CardNotFound)?;
+        if card.locked_by_action != Action::Stake {
+            return Err(NFTError::StakeNotFound);
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey2::CompoundPreference(player.clone()), preference);
+    Ok(())
+}
+
+/// Puts claimed `power` to work as the player prefers. Returns what is left for
+/// `User.power`.
+pub fn compound(env: &Env, player: &Address, power: u32) -> u32 {
+    let (to_deck, to_stake) = match read_auto_compound(env, player) {
+        AutoCompound::Off => return power,
+        AutoCompound::Deck => (deck::add_power(env, player.clone(), power), 0),
+        AutoCompound::Stake(category, token_id) => {
+            let staked = stake::compound_stake_power(env, player.clone(), category, token_id, power);
+            (0, staked.unwrap_or(0))
+        }
+    };
+    let remainder = power - to_deck - to_stake;
+    emit_power_compounded(env, player, to_deck, to_stake, remainder);
+    remainder
+}
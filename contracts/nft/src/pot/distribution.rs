@@ -0,0 +1,484 @@
+use crate::admin::{read_user_claimable_balance, write_user_claimable_balance};
+use crate::ledger::{post_all, Account};
+use crate::nft_info::Action;
+use crate::pot::expiry;
+use crate::pot::management::{get_current_round, round_after};
+use crate::pot::tokens;
+use crate::storage_types::{DataKey2, PotAmounts, UserClaimableBalance};
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// Fixed-point scale of the reward rates and indexes.
+pub const REWARD_INDEX_SCALE: i128 = 1_000_000_000_000;
+
+/// Most closed periods one claim settles. A player further behind catches up over
+/// several claims; deck changes settle every period first.
+pub const MAX_SETTLED_PERIODS: u32 = 50;
+
+/// Global distribution state. Rewards go to effective power × time held: each
+/// opening pays the pot out over the power-seconds of the period it closes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardIndex {
+    /// Rewards per unit of effective power held through every closed period,
+    /// in REWARD_INDEX_SCALE fixed point.
+    pub terry: i128,
+    pub power: i128,
+    pub xtar: i128,
+    /// Summed effective power of every complete deck.
+    pub total_effective_power: u32,
+    /// Players with a complete deck.
+    pub participants: u32,
+    /// Start of the running period, i.e. the last opening.
+    pub period_start: u64,
+    /// Power-seconds held during the running period, up to `updated`.
+    pub power_time: i128,
+    pub updated: u64,
+    /// Round whose period was closed last.
+    pub last_period: u32,
+}
+
+/// A closed period, keyed by the round that opened it. Rates are rewards per
+/// power-second in REWARD_INDEX_SCALE fixed point; indexes are the global
+/// indexes once the period closed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RewardPeriod {
+    pub start: u64,
+    pub end: u64,
+    pub power_time: i128,
+    pub terry_rate: i128,
+    pub power_rate: i128,
+    pub xtar_rate: i128,
+    pub terry_index: i128,
+    pub power_index: i128,
+    pub xtar_index: i128,
+}
+
+/// A player's effective power and how far its rewards have been settled.
+#[contracttype]
+#[derive(Clone)]
+struct RewardCheckpoint {
+    effective_power: u32,
+    /// Round whose period the player was last settled in, and when.
+    period: u32,
+    updated: u64,
+    /// Power-seconds held in `period` up to `updated`.
+    power_time: i128,
+    /// Power-seconds held in the period closed just before `period`.
+    closed_period: u32,
+    closed_power_time: i128,
+}
+
+/// Effective power a player switched to in the period of round `period` while
+/// still behind on earlier closed periods, which keep the checkpoint's power.
+#[contracttype]
+#[derive(Clone)]
+struct PowerChange {
+    period: u32,
+    /// Power-seconds held in `period` up to `updated`.
+    power_time: i128,
+    updated: u64,
+    /// Held from `updated` on.
+    effective_power: u32,
+}
+
+pub fn read_reward_index(env: &Env) -> RewardIndex {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::Distribution)
+        .unwrap_or(RewardIndex {
+            terry: 0,
+            power: 0,
+            xtar: 0,
+            total_effective_power: 0,
+            participants: 0,
+            period_start: env.ledger().timestamp(),
+            power_time: 0,
+            updated: env.ledger().timestamp(),
+            last_period: 0,
+        })
+}
+
+fn write_reward_index(env: &Env, index: &RewardIndex) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::Distribution, index);
+}
+
+pub fn read_reward_period(env: &Env, period: u32) -> Option<RewardPeriod> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::RewardPeriod(period))
+}
+
+fn read_checkpoint(env: &Env, player: &Address) -> RewardCheckpoint {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::PlayerCheckpoint(player.clone()))
+        .unwrap_or(RewardCheckpoint {
+            effective_power: 0,
+            period: get_current_round(env),
+            updated: env.ledger().timestamp(),
+            power_time: 0,
+            closed_period: 0,
+            closed_power_time: 0,
+        })
+}
+
+/// Power changes not reached by settlement yet, oldest first.
+fn read_power_changes(env: &Env, player: &Address) -> Vec<PowerChange> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::PowerChanges(player.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+fn write_power_changes(env: &Env, player: &Address, changes: &Vec<PowerChange>) {
+    let key = DataKey2::PowerChanges(player.clone());
+    if changes.is_empty() {
+        env.storage().persistent().remove(&key);
+    } else {
+        env.storage().persistent().set(&key, changes);
+    }
+}
+
+/// Effective power the player currently earns pot rewards with.
+pub fn read_effective_power(env: &Env, player: &Address) -> u32 {
+    read_power_changes(env, player)
+        .last()
+        .map_or(read_checkpoint(env, player).effective_power, |change| change.effective_power)
+}
+
+/// Brings the running period's power-seconds up to now.
+fn update_power_time(env: &Env, index: &mut RewardIndex) {
+    let now = env.ledger().timestamp();
+    index.power_time += index.total_effective_power as i128 * (now - index.updated) as i128;
+    index.updated = now;
+}
+
+/// Power-seconds held by everyone during the running period so far.
+pub fn read_power_time(env: &Env) -> i128 {
+    let mut index = read_reward_index(env);
+    update_power_time(env, &mut index);
+    index.power_time
+}
+
+/// Power-seconds the player held during the period of round `period`, up to now
+/// for the running period. Only the player's last two periods are known.
+pub fn read_player_power_time(env: &Env, player: &Address, period: u32) -> i128 {
+    let checkpoint = read_checkpoint(env, player);
+    let power = checkpoint.effective_power as i128;
+    let (start, end) = match read_reward_period(env, period) {
+        Some(closed) => (closed.start, closed.end),
+        None => (read_reward_index(env).period_start, env.ledger().timestamp()),
+    };
+    if checkpoint.period == period {
+        checkpoint.power_time + power * (end - checkpoint.updated) as i128
+    } else if checkpoint.period < period {
+        // The last power change up to `period` sets what the player held in it
+        let mut power_time = power * (end - start) as i128;
+        for change in read_power_changes(env, player).iter() {
+            if change.period > period {
+                break;
+            }
+            let held = change.effective_power as i128;
+            power_time = if change.period == period {
+                change.power_time + held * (end - change.updated) as i128
+            } else {
+                held * (end - start) as i128
+            };
+        }
+        power_time
+    } else if checkpoint.closed_period == period {
+        checkpoint.closed_power_time
+    } else {
+        0
+    }
+}
+
+/// Rate that spreads `amount` over `power_time`, and the amount it assigns. The
+/// assigned amount is rounded down, so it never exceeds `amount` nor falls short
+/// of the players' rounded-down accruals; the dust left of `amount` stays in the
+/// pot and rolls over to the next round.
+pub fn spread(amount: i128, power_time: i128) -> (i128, i128) {
+    if amount <= 0 || power_time <= 0 {
+        return (0, 0);
+    }
+    let rate = amount * REWARD_INDEX_SCALE / power_time;
+    (rate, rate * power_time / REWARD_INDEX_SCALE)
+}
+
+/// Amount earned by `power_time` in a period paying `rate`, plus `effective_power`
+/// held while an index rose from `from` to `to`.
+pub fn earned(rate: i128, power_time: i128, effective_power: u32, from: i128, to: i128) -> i128 {
+    (rate * power_time + effective_power as i128 * (to - from)) / REWARD_INDEX_SCALE
+}
+
+/// The closed period the player has not been settled for, and its power-seconds
+/// in it. `None` while the player's period is still running.
+fn unsettled(env: &Env, checkpoint: &RewardCheckpoint) -> Option<(RewardPeriod, i128)> {
+    if checkpoint.period == get_current_round(env) {
+        return None;
+    }
+    let period = read_reward_period(env, checkpoint.period)?;
+    let power_time = checkpoint.power_time
+        + checkpoint.effective_power as i128 * (period.end - checkpoint.updated) as i128;
+    Some((period, power_time))
+}
+
+/// Unsettled closed period of a player: its round, power-seconds and effective power.
+pub fn read_unsettled(env: &Env, player: &Address) -> Option<(u32, i128, u32)> {
+    let checkpoint = read_checkpoint(env, player);
+    unsettled(env, &checkpoint)
+        .map(|(_, power_time)| (checkpoint.period, power_time, checkpoint.effective_power))
+}
+
+/// Closed periods not settled yet, walked oldest first.
+struct Walk {
+    /// Rewards of each period walked, under the round that paid them out.
+    rewards: Vec<(u32, PotAmounts)>,
+    /// Power-seconds the player held in its checkpoint period.
+    own_power_time: i128,
+    /// Last period walked, the player's power-seconds in it, and when it closed.
+    last: Option<(u32, i128, u64)>,
+    /// Period after the last one walked; the running period once caught up.
+    next: u32,
+}
+
+/// Walks at most `limit` of the closed periods since the player's checkpoint,
+/// up to the period of round `stop`, attributing each period's rewards to the
+/// round that closed it. Amounts are differences of the running total earned
+/// since the checkpoint, so splitting by period rounds no more than settling
+/// everything at once.
+fn walk(env: &Env, checkpoint: &RewardCheckpoint, limit: u32, stop: u32) -> Walk {
+    let power = checkpoint.effective_power;
+    let mut walk = Walk {
+        rewards: Vec::new(env),
+        own_power_time: 0,
+        last: None,
+        next: checkpoint.period,
+    };
+    if checkpoint.period == stop {
+        return walk;
+    }
+    let Some(own) = read_reward_period(env, checkpoint.period) else {
+        return walk;
+    };
+    walk.own_power_time =
+        checkpoint.power_time + power as i128 * (own.end - checkpoint.updated) as i128;
+
+    let mut total = PotAmounts { terry: 0, power: 0, xtar: 0 };
+    while walk.next != stop && walk.rewards.len() < limit {
+        let closed = if walk.next == checkpoint.period {
+            Some(own.clone())
+        } else {
+            read_reward_period(env, walk.next)
+        };
+        let (Some(closed), Some(paid_in)) = (closed, round_after(env, walk.next)) else {
+            break;
+        };
+        let pt = walk.own_power_time;
+        let so_far = PotAmounts {
+            terry: earned(own.terry_rate, pt, power, own.terry_index, closed.terry_index),
+            power: earned(own.power_rate, pt, power, own.power_index, closed.power_index) as u32,
+            xtar: earned(own.xtar_rate, pt, power, own.xtar_index, closed.xtar_index),
+        };
+        walk.rewards.push_back((
+            paid_in,
+            PotAmounts {
+                terry: so_far.terry - total.terry,
+                power: so_far.power - total.power,
+                xtar: so_far.xtar - total.xtar,
+            },
+        ));
+        let power_time = if walk.next == checkpoint.period {
+            pt
+        } else {
+            power as i128 * (closed.end - closed.start) as i128
+        };
+        walk.last = Some((walk.next, power_time, closed.end));
+        walk.next = paid_in;
+        total = so_far;
+    }
+    walk
+}
+
+/// Claimable balance including the rewards the player's next claim settles,
+/// i.e. those of up to `MAX_SETTLED_PERIODS` closed periods.
+pub fn read_claimable(env: &Env, player: &Address) -> UserClaimableBalance {
+    let mut claimable = read_user_claimable_balance(env, player);
+    let checkpoint = read_checkpoint(env, player);
+    let stop = read_power_changes(env, player)
+        .first()
+        .map_or(get_current_round(env), |change| change.period);
+    for (round, amounts) in walk(env, &checkpoint, MAX_SETTLED_PERIODS, stop).rewards.iter() {
+        expiry::add(env, &mut claimable, round, &amounts);
+    }
+    claimable
+}
+
+/// Moves the player's rewards of up to `limit` closed periods, sponsor tokens
+/// included, into its claimable balances. Settlement resumes at each power
+/// change it reaches with the power the player switched to. Returns the
+/// checkpoint to store.
+fn settle(env: &Env, player: &Address, index: &RewardIndex, checkpoint: &RewardCheckpoint, limit: u32) -> RewardCheckpoint {
+    let now = env.ledger().timestamp();
+    let current = get_current_round(env);
+    let mut changes = read_power_changes(env, player);
+    let mut next = checkpoint.clone();
+    let mut limit = limit;
+
+    loop {
+        let power = next.effective_power as i128;
+        let stop = changes.first().map_or(current, |change| change.period);
+        let walk = walk(env, &next, limit, stop);
+        let Some((last, last_power_time, last_end)) = walk.last else {
+            if next.period == current {
+                next.power_time += power * (now - next.updated) as i128;
+                next.updated = now;
+            }
+            break;
+        };
+        let rewards = walk.rewards;
+        if rewards.iter().any(|(_, a)| a.terry > 0 || a.power > 0 || a.xtar > 0) {
+            let mut claimable = read_user_claimable_balance(env, player);
+            for (round, amounts) in rewards.iter() {
+                expiry::add(env, &mut claimable, round, &amounts);
+            }
+            write_user_claimable_balance(env, player, &claimable);
+        }
+        tokens::settle(env, player, next.period, walk.own_power_time, next.effective_power, last);
+        limit -= rewards.len();
+
+        next.closed_period = last;
+        next.closed_power_time = last_power_time;
+        next.period = walk.next;
+        if walk.next == stop && !changes.is_empty() {
+            // Reached the period the player's power changed in: carry on with the new power
+            let change = changes.pop_front().unwrap();
+            next.effective_power = change.effective_power;
+            next.power_time = change.power_time;
+            next.updated = change.updated;
+            if walk.next == current {
+                next.power_time += change.effective_power as i128 * (now - change.updated) as i128;
+                next.updated = now;
+            }
+        } else if walk.next == current {
+            next.power_time = power * (now - index.period_start) as i128;
+            next.updated = now;
+        } else {
+            // Still behind: resume from the start of the next closed period
+            next.power_time = 0;
+            next.updated = last_end;
+        }
+        if next.period == current || limit == 0 {
+            break;
+        }
+    }
+    write_power_changes(env, player, &changes);
+    next
+}
+
+fn write_checkpoint(env: &Env, player: &Address, checkpoint: &RewardCheckpoint) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::PlayerCheckpoint(player.clone()), checkpoint);
+}
+
+/// Settles what the player has accrued in up to `MAX_SETTLED_PERIODS` closed periods.
+pub fn accrue(env: &Env, player: &Address) {
+    let checkpoint = read_checkpoint(env, player);
+    let next = settle(env, player, &read_reward_index(env), &checkpoint, MAX_SETTLED_PERIODS);
+    write_checkpoint(env, player, &next);
+}
+
+/// Records a player's new effective power after its deck changed, settling up to
+/// `MAX_SETTLED_PERIODS` closed periods first. A player still behind after that
+/// keeps its old power for the closed periods left; the new power is recorded as
+/// a change in the running period, where later settlement picks it up.
+pub fn set_effective_power(env: &Env, player: &Address, effective_power: u32) {
+    let previous = read_effective_power(env, player);
+    if previous == effective_power {
+        return;
+    }
+    let mut index = read_reward_index(env);
+    update_power_time(env, &mut index);
+    let checkpoint = read_checkpoint(env, player);
+    let mut next = settle(env, player, &index, &checkpoint, MAX_SETTLED_PERIODS);
+
+    let current = get_current_round(env);
+    if next.period == current {
+        next.effective_power = effective_power;
+    } else {
+        let now = env.ledger().timestamp();
+        let mut changes = read_power_changes(env, player);
+        match changes.last() {
+            Some(mut change) if change.period == current => {
+                change.power_time += change.effective_power as i128 * (now - change.updated) as i128;
+                change.updated = now;
+                change.effective_power = effective_power;
+                changes.set(changes.len() - 1, change);
+            }
+            last => {
+                let held = last.map_or(next.effective_power, |change| change.effective_power);
+                changes.push_back(PowerChange {
+                    period: current,
+                    power_time: held as i128 * (now - index.period_start) as i128,
+                    updated: now,
+                    effective_power,
+                });
+            }
+        }
+        write_power_changes(env, player, &changes);
+    }
+    write_checkpoint(env, player, &next);
+
+    index.total_effective_power = index.total_effective_power - previous + effective_power;
+    if previous == 0 {
+        index.participants += 1;
+    } else if effective_power == 0 {
+        index.participants -= 1;
+    }
+    write_reward_index(env, &index);
+}
+
+/// Closes the running period, which belongs to round `period`, and pays pot amounts
+/// out over its power-seconds by raising the indexes. Moves the assigned amounts
+/// from the pot to claimable; touches only global state. Returns the closed period.
+pub fn close_period(env: &Env, period: u32, terry: i128, power: u32, xtar: i128) -> RewardPeriod {
+    let mut index = read_reward_index(env);
+    update_power_time(env, &mut index);
+    let now = index.updated;
+    let duration = (now - index.period_start) as i128;
+
+    let (terry_rate, terry) = spread(terry, index.power_time);
+    let (power_rate, power) = spread(power as i128, index.power_time);
+    let (xtar_rate, xtar) = spread(xtar, index.power_time);
+    index.terry += terry_rate * duration;
+    index.power += power_rate * duration;
+    index.xtar += xtar_rate * duration;
+
+    let closed = RewardPeriod {
+        start: index.period_start,
+        end: now,
+        power_time: index.power_time,
+        terry_rate,
+        power_rate,
+        xtar_rate,
+        terry_index: index.terry,
+        power_index: index.power,
+        xtar_index: index.xtar,
+    };
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RewardPeriod(period), &closed);
+
+    index.period_start = now;
+    index.power_time = 0;
+    index.last_period = period;
+    write_reward_index(env, &index);
+    if terry > 0 || power > 0 || xtar > 0 {
+        post_all(env, Account::Pot, Account::Claimable, terry, power as u32, xtar, Action::None);
+    }
+    closed
+}
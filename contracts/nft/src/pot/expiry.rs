@@ -0,0 +1,100 @@
+use crate::admin::{read_config, read_user_claimable_balance, write_user_claimable_balance};
+use crate::event::emit_claimable_expired;
+use crate::ledger::{post_all, Account};
+use crate::nft_info::Action;
+use crate::pot::management::{get_current_round, read_pot_snapshot};
+use crate::pot::reward::{by_round, record_claim};
+use crate::storage_types::{ClaimableRound, PotAmounts, RewardStatus, UserClaimableBalance};
+use soroban_sdk::{Address, Env, Vec};
+
+/// Adds rewards paid in `round` to `claimable`, under that round's entry. Does
+/// not write the balance.
+pub fn add(env: &Env, claimable: &mut UserClaimableBalance, round: u32, amounts: &PotAmounts) {
+    if amounts.terry == 0 && amounts.power == 0 && amounts.xtar == 0 {
+        return;
+    }
+    claimable.terry += amounts.terry;
+    claimable.power += amounts.power;
+    claimable.xtar += amounts.xtar;
+
+    let position = claimable.rounds.iter().position(|entry| entry.round == round);
+    let mut entry = match position {
+        Some(i) => claimable.rounds.get(i as u32).unwrap(),
+        None => {
+            let config = read_config(env);
+            let expiry = config.claim_expiry_rounds;
+            // Vesting runs from the round's opening
+            let start = read_pot_snapshot(env, round)
+                .map(|snapshot| snapshot.timestamp)
+                .unwrap_or(env.ledger().timestamp());
+            ClaimableRound {
+                round,
+                terry: 0,
+                power: 0,
+                xtar: 0,
+                expires_round: if expiry == 0 { 0 } else { round.saturating_add(expiry) },
+                vesting_start: start,
+                vesting_end: start.saturating_add(config.vesting_period),
+            }
+        }
+    };
+    entry.terry += amounts.terry;
+    entry.power += amounts.power;
+    entry.xtar += amounts.xtar;
+    match position {
+        Some(i) => claimable.rounds.set(i as u32, entry),
+        None => claimable.rounds.push_back(entry),
+    }
+}
+
+/// Credits `player` rewards paid in `round`.
+pub fn credit(env: &Env, player: &Address, round: u32, amounts: &PotAmounts) {
+    let mut claimable = read_user_claimable_balance(env, player);
+    add(env, &mut claimable, round, amounts);
+    write_user_claimable_balance(env, player, &claimable);
+}
+
+/// Returns the player's expired rewards to the pot. The player must have been
+/// settled first. Returns the swept amounts.
+pub fn sweep(env: &Env, player: &Address) -> PotAmounts {
+    let current_round = get_current_round(env);
+    let mut claimable = read_user_claimable_balance(env, player);
+    let mut swept = PotAmounts {
+        terry: 0,
+        power: 0,
+        xtar: 0,
+    };
+    let mut kept = Vec::new(env);
+    let mut expired = Vec::new(env);
+    for entry in claimable.rounds.iter() {
+        if entry.expires_round != 0 && current_round >= entry.expires_round {
+            swept.terry += entry.terry;
+            swept.power += entry.power;
+            swept.xtar += entry.xtar;
+            expired.push_back(entry);
+        } else {
+            kept.push_back(entry);
+        }
+    }
+    if swept.terry == 0 && swept.power == 0 && swept.xtar == 0 {
+        return swept;
+    }
+
+    claimable.terry -= swept.terry;
+    claimable.power -= swept.power;
+    claimable.xtar -= swept.xtar;
+    claimable.rounds = kept;
+    write_user_claimable_balance(env, player, &claimable);
+    record_claim(env, player, by_round(env, &expired, &swept, RewardStatus::Failed));
+    post_all(
+        env,
+        Account::Claimable,
+        Account::Pot,
+        swept.terry,
+        swept.power,
+        swept.xtar,
+        Action::None,
+    );
+    emit_claimable_expired(env, player, current_round, &swept);
+    swept
+}
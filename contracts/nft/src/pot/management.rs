@@ -1,18 +1,22 @@
 use crate::actions::deck::read_deck;
 use crate::event::*;
 use crate::storage_types::{
-    DataKey, Deck, DogstarBalance, PendingReward, PlayerReward, PotBalance, PotSnapshot, TokenId,
+    DataKey, DataKey2, Deck, DogstarBalance, LegacyPlayerReward, LegacyPotSnapshot, PlayerReward,
+    PotBalance, PotSnapshot, TokenId,
 };
-use crate::admin::{read_config};
-use crate::storage_types::UserClaimableBalance;
+use crate::fees::read_action_fees;
+use crate::ledger::{post_all, read_account, Account};
+use crate::pot::expiry::credit;
+use crate::storage_types::{PotAmounts, UserClaimableBalance};
 use crate::nft_info::{Action, Category, read_nft};
 use crate::metadata::read_metadata;
 use crate::user_info::read_user;
-use soroban_sdk::{Address, Env, Vec};
+use crate::pot::modes::DistributionMode;
+use soroban_sdk::{symbol_short, Address, Env, FromVal, Map, Symbol, Val, Vec};
 
 const DAY_IN_LEDGERS: u32 = 17280;
-const POT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
-const POT_LIFETIME_THRESHOLD: u32 = POT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub const POT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub const POT_LIFETIME_THRESHOLD: u32 = POT_BUMP_AMOUNT - DAY_IN_LEDGERS;
 
 /// Calculates effective power by applying the deck bonus to base power.
 pub fn calculate_effective_power(base_power: u32, deck_bonus: u32) -> u32 {
@@ -20,8 +24,10 @@ pub fn calculate_effective_power(base_power: u32, deck_bonus: u32) -> u32 {
 }
 
 // Pot Balance Management
+/// Reads the pot record with accumulated amounts taken from the ledger's pot account.
 pub fn read_pot_balance(env: &Env) -> PotBalance {
-    env.storage()
+    let mut balance = env
+        .storage()
         .persistent()
         .get(&DataKey::PotBalance)
         .unwrap_or(PotBalance {
@@ -31,7 +37,12 @@ pub fn read_pot_balance(env: &Env) -> PotBalance {
             last_opening_round: 0,
             total_openings: 0,
             last_updated: env.ledger().timestamp(),
-        })
+        });
+    let pot = read_account(env, Account::Pot);
+    balance.accumulated_terry = pot.terry;
+    balance.accumulated_power = pot.power as u32;
+    balance.accumulated_xtar = pot.xtar;
+    balance
 }
 
 pub fn write_pot_balance(env: &Env, balance: &PotBalance) {
@@ -40,54 +51,72 @@ pub fn write_pot_balance(env: &Env, balance: &PotBalance) {
         .set(&DataKey::PotBalance, balance);
 }
 
+/// Dogstar fees owed, released or not.
 pub fn read_dogstar_balance(env: &Env) -> DogstarBalance {
-    env.storage()
-        .persistent()
-        .get(&DataKey::DogstarBalance)
-        .unwrap_or(DogstarBalance {
-            terry: 0,
-            power: 0,
-            xtar: 0,
-        })
-}
-
-pub fn write_dogstar_balance(env: &Env, balance: &DogstarBalance) {
-    env.storage()
-        .persistent()
-        .set(&DataKey::DogstarBalance, balance);
+    let fees = read_account(env, Account::DogstarFees);
+    let claimable = read_account(env, Account::DogstarClaimable);
+    DogstarBalance {
+        terry: fees.terry + claimable.terry,
+        power: (fees.power + claimable.power) as u32,
+        xtar: fees.xtar + claimable.xtar,
+    }
 }
 
 // Internal helper to accumulate pot balances and dogstar fees without requiring admin auth.
 // This is intended to be called from trusted internal flows like mint/burn.
 pub fn accumulate_pot_internal(env: &Env, terry: i128, power: u32, xtar: i128, from: Option<Address>, action: Option<Action>) {
-    let config = read_config(env);
     let mut pot_balance = read_pot_balance(env);
+    let action_val = action.clone().unwrap_or(Action::None);
 
-    // Calculate fees in basis points
-    let fee_percentage = config.dogstar_fee_percentage;
+    // Calculate fees in basis points, using the fee schedule of the originating action
+    let fee_percentage = read_action_fees(env, &action_val).protocol_fee_bps;
     let terry_fee = (terry * fee_percentage as i128) / 10000;
     let power_fee = (power * fee_percentage) / 10000;
     let xtar_fee = (xtar * fee_percentage as i128) / 10000;
 
-    // Accumulate in pot balance (minus dogstar fees)
-    pot_balance.accumulated_terry += terry - terry_fee;
-    pot_balance.accumulated_power += power - power_fee;
-    pot_balance.accumulated_xtar += xtar - xtar_fee;
+    // Pot receives the contribution minus Dogstar fees, which wait in their own account
+    post_all(
+        env,
+        Account::External,
+        Account::Pot,
+        terry - terry_fee,
+        power - power_fee,
+        xtar - xtar_fee,
+        action_val.clone(),
+    );
+    post_all(
+        env,
+        Account::External,
+        Account::DogstarFees,
+        terry_fee,
+        power_fee,
+        xtar_fee,
+        action_val,
+    );
     pot_balance.last_updated = env.ledger().timestamp();
     write_pot_balance(env, &pot_balance);
 
-    // Update legacy dogstar balance for backward-compat events/UI
-    let mut dogstar_balance = read_dogstar_balance(env);
-    dogstar_balance.terry += terry_fee;
-    dogstar_balance.power += power_fee;
-    dogstar_balance.xtar += xtar_fee;
-    write_dogstar_balance(env, &dogstar_balance);
-
     if terry_fee > 0 || power_fee > 0 || xtar_fee > 0 {
         emit_dogstar_fee_accumulated(env, terry_fee, power_fee, xtar_fee, fee_percentage, from, action);
     }
 }
 
+/// Credits `keeper` `reward_bps` of the pot as a claimable balance, ahead of
+/// the opening of `round`. Returns the (TERRY, POWER, XTAR) reward.
+pub fn pay_keeper_reward(env: &Env, keeper: &Address, reward_bps: u32) -> (i128, u32, i128) {
+    let pot = read_account(env, Account::Pot);
+    let terry = pot.terry * reward_bps as i128 / 10000;
+    let power = (pot.power * reward_bps as i128 / 10000) as u32;
+    let xtar = pot.xtar * reward_bps as i128 / 10000;
+    if terry == 0 && power == 0 && xtar == 0 {
+        return (0, 0, 0);
+    }
+
+    credit(env, keeper, get_current_round(env), &PotAmounts { terry, power, xtar });
+    post_all(env, Account::Pot, Account::Claimable, terry, power, xtar, Action::None);
+    (terry, power, xtar)
+}
+
 // Snapshot Management
 pub fn write_pot_snapshot(env: &Env, round: u32, snapshot: &PotSnapshot) {
     let key = DataKey::OpeningSnapshot(round);
@@ -95,10 +124,42 @@ pub fn write_pot_snapshot(env: &Env, round: u32, snapshot: &PotSnapshot) {
     env.storage().persistent().set(&key, snapshot);
 }
 
+/// Reads the snapshot of `round`. Rounds opened before snapshots recorded their
+/// split read as proportional openings that paid out the whole pot.
 pub fn read_pot_snapshot(env: &Env, round: u32) -> Option<PotSnapshot> {
-    env.storage()
+    let stored = env
+        .storage()
         .persistent()
-        .get(&DataKey::OpeningSnapshot(round))
+        .get::<_, Map<Symbol, Val>>(&DataKey::OpeningSnapshot(round))?;
+    if stored.contains_key(symbol_short!("winners")) {
+        return Some(PotSnapshot::from_val(env, &stored.to_val()));
+    }
+    let legacy = LegacyPotSnapshot::from_val(env, &stored.to_val());
+    let none = PotAmounts {
+        terry: 0,
+        power: 0,
+        xtar: 0,
+    };
+    Some(PotSnapshot {
+        round_number: legacy.round_number,
+        total_terry: legacy.total_terry,
+        total_power: legacy.total_power,
+        total_xtar: legacy.total_xtar,
+        timestamp: legacy.timestamp,
+        total_participants: legacy.total_participants,
+        total_effective_power: legacy.total_effective_power,
+        time_weighted_power: 0,
+        distributed: PotAmounts {
+            terry: legacy.total_terry,
+            power: legacy.total_power,
+            xtar: legacy.total_xtar,
+        },
+        rolled_over: none.clone(),
+        reserved: none.clone(),
+        jackpot: none,
+        mode: DistributionMode::Proportional,
+        winners: Vec::new(env),
+    })
 }
 
 pub fn write_player_reward(env: &Env, round: u32, player: &Address, reward: &PlayerReward) {
@@ -107,24 +168,25 @@ pub fn write_player_reward(env: &Env, round: u32, player: &Address, reward: &Pla
     env.storage().persistent().set(&key, reward);
 }
 
+/// Reads a player's share of `round`; shares stored before winners were ranked
+/// read with rank 0.
 pub fn read_player_reward(env: &Env, round: u32, player: &Address) -> Option<PlayerReward> {
-    env.storage()
-        .persistent()
-        .get(&DataKey::PlayerShare(round, player.clone()))
-}
-
-pub fn write_pending_reward(env: &Env, round: u32, player: &Address, reward: &PendingReward) {
-    let key = DataKey::PendingReward(round, player.clone());
-    env.storage()
-        .persistent()
-        .extend_ttl(&key, POT_LIFETIME_THRESHOLD, POT_BUMP_AMOUNT);
-    env.storage().persistent().set(&key, reward);
-}
-
-pub fn read_pending_reward(env: &Env, round: u32, player: &Address) -> Option<PendingReward> {
-    env.storage()
+    let stored = env
+        .storage()
         .persistent()
-        .get(&DataKey::PendingReward(round, player.clone()))
+        .get::<_, Map<Symbol, Val>>(&DataKey::PlayerShare(round, player.clone()))?;
+    if stored.contains_key(symbol_short!("rank")) {
+        return Some(PlayerReward::from_val(env, &stored.to_val()));
+    }
+    let legacy = LegacyPlayerReward::from_val(env, &stored.to_val());
+    Some(PlayerReward {
+        share_percentage: legacy.share_percentage,
+        effective_power: legacy.effective_power,
+        round_number: legacy.round_number,
+        deck_bonus: legacy.deck_bonus,
+        deck_categories: legacy.deck_categories,
+        rank: 0,
+    })
 }
 
 pub fn get_current_round(env: &Env) -> u32 {
@@ -147,13 +209,65 @@ pub fn get_all_rounds(env: &Env) -> Vec<u32> {
         .unwrap_or_else(|| Vec::new(env))
 }
 
+/// Round opened before `round`, whose period `round` paid out; 0 for the first.
+pub fn previous_round(env: &Env, round: u32) -> u32 {
+    if let Some(previous) = env.storage().persistent().get(&DataKey2::RoundBefore(round)) {
+        return previous;
+    }
+    // Rounds opened before the links were kept, until `link_rounds` runs
+    let rounds = get_all_rounds(env);
+    match rounds.first_index_of(round) {
+        Some(i) if i > 0 => rounds.get(i - 1).unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// Round opened after `round`, which paid out `round`'s period; round 0 stands
+/// for the period before the first round. `None` for the current round.
+pub fn round_after(env: &Env, round: u32) -> Option<u32> {
+    if let Some(next) = env.storage().persistent().get(&DataKey2::RoundAfter(round)) {
+        return Some(next);
+    }
+    let rounds = get_all_rounds(env);
+    let position = if round == 0 { Some(0) } else { rounds.first_index_of(round).map(|i| i + 1) };
+    position.and_then(|i| rounds.get(i))
+}
+
+fn link_round(env: &Env, previous: u32, round: u32) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RoundAfter(previous), &round);
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RoundBefore(round), &previous);
+}
+
 pub fn add_round(env: &Env, round: u32) {
     let mut rounds = get_all_rounds(env);
+    link_round(env, rounds.last().unwrap_or(0), round);
     rounds.push_back(round);
 
     env.storage().persistent().set(&DataKey::AllRounds, &rounds);
 }
 
+/// Links every round to its neighbours for contracts that opened rounds before
+/// the links were kept. Returns false when they are linked already.
+pub fn link_rounds(env: &Env) -> bool {
+    let rounds = get_all_rounds(env);
+    let Some(first) = rounds.first() else {
+        return false;
+    };
+    if env.storage().persistent().has(&DataKey2::RoundBefore(first)) {
+        return false;
+    }
+    let mut previous = 0;
+    for round in rounds.iter() {
+        link_round(env, previous, round);
+        previous = round;
+    }
+    true
+}
+
 pub fn get_eligible_players(env: &Env) -> Vec<Address> {
     let mut eligible_players = Vec::new(env);
     let decks = env
@@ -212,43 +326,3 @@ pub fn get_eligible_players_with_shares(env: &Env) -> Vec<(Address, u32, u32, u3
     }
     result
 }
-
-pub fn calculate_player_shares(env: &Env, round: u32) {
-    let players = get_eligible_players(env);
-    let mut total_effective_power: u32 = 0;
-    let mut player_powers = Vec::new(env);
-
-    for player in players.iter() {
-        let deck = read_deck(env.clone(), player.clone());
-        if deck.token_ids.len() == 4 {
-            let effective_power = calculate_effective_power(deck.total_power, deck.bonus);
-            total_effective_power += effective_power;
-            player_powers.push_back((player, effective_power, deck.bonus, deck.deck_categories));
-        }
-    }
-
-    for (player, effective_power, deck_bonus, deck_categories) in player_powers.iter() {
-        let share_percentage = if total_effective_power > 0 {
-            (effective_power * 10000) / total_effective_power // Basis points
-        } else {
-            0
-        };
-
-        let reward = PlayerReward {
-            share_percentage,
-            effective_power,
-            round_number: round,
-            deck_bonus,
-            deck_categories,
-        };
-
-        write_player_reward(env, round, &player, &reward);
-        emit_share_calculated(env, &player, &reward);
-    }
-
-    if let Some(mut snapshot) = read_pot_snapshot(env, round) {
-        snapshot.total_participants = player_powers.len();
-        snapshot.total_effective_power = total_effective_power;
-        write_pot_snapshot(env, round, &snapshot);
-    }
-}
@@ -1,2 +1,11 @@
+pub mod audit;
+pub mod compound;
+pub mod distribution;
+pub mod expiry;
 pub mod management;
+pub mod modes;
+pub mod opening;
+pub mod reserve;
 pub mod reward;
+pub mod tokens;
+pub mod vesting;
@@ -0,0 +1,258 @@
+use crate::actions::read_deck;
+use crate::admin::{read_administrator, read_config};
+use crate::governance::timelock::ensure_no_timelock;
+use crate::error::NFTError;
+use crate::event::{emit_distribution_mode_set, emit_pot_winner};
+use crate::ledger::{post_all, Account};
+use crate::nft_info::Action;
+use crate::pot::distribution::read_effective_power;
+use crate::pot::expiry::credit;
+use crate::pot::management::{get_current_round, write_player_reward};
+use crate::pot::tokens;
+use crate::storage_types::{DataKey2, PlayerReward, PotAmounts};
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// Most tiers a tiered distribution may pay.
+pub const MAX_TIERS: u32 = 10;
+/// Most winners a lottery may draw.
+pub const MAX_LOTTERY_WINNERS: u32 = 10;
+
+/// How an opening pays out the pot. Players are weighed by effective power ×
+/// seconds held over the period the opening closes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DistributionMode {
+    /// Every player in proportion to its weight.
+    Proportional,
+    /// The heaviest players take these shares in basis points, first place first.
+    /// Tiers without a player leave their share in the pot.
+    Tiered(Vec<u32>),
+    /// This many winners drawn without replacement, weighted by weight, share
+    /// the pot equally.
+    Lottery(u32),
+}
+
+/// A player paid by a tiered or lottery opening.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PotWinner {
+    pub player: Address,
+    /// Share of the pot in basis points.
+    pub share_bps: u32,
+}
+
+pub fn read_distribution_mode(env: &Env, round: u32) -> DistributionMode {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::RoundMode(round))
+        .unwrap_or(DistributionMode::Proportional)
+}
+
+/// Sets how the opening of `round` pays out. Admin only, and only while no
+/// timelock is configured; otherwise the change goes through the queue.
+pub fn set_distribution_mode(env: Env, round: u32, mode: DistributionMode) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+    ensure_no_timelock(&read_config(&env))?;
+
+    validate_distribution_mode(&env, round, &mode)?;
+    write_distribution_mode(&env, round, &mode);
+    Ok(())
+}
+
+/// Checks that `mode` can pay out the future `round`. Tiers must be non-zero and
+/// sum to 10000 bps (`InvalidPercentage`); a lottery needs 1 to
+/// MAX_LOTTERY_WINNERS winners (`InvalidAmount`).
+pub fn validate_distribution_mode(env: &Env, round: u32, mode: &DistributionMode) -> Result<(), NFTError> {
+    if round <= get_current_round(env) {
+        return Err(NFTError::RoundAlreadyProcessed);
+    }
+    match mode {
+        DistributionMode::Proportional => {}
+        DistributionMode::Tiered(tiers) => {
+            if tiers.is_empty()
+                || tiers.len() > MAX_TIERS
+                || tiers.iter().any(|bps| bps == 0)
+                || tiers.iter().sum::<u32>() != 10000
+            {
+                return Err(NFTError::InvalidPercentage);
+            }
+        }
+        DistributionMode::Lottery(winners) => {
+            if *winners == 0 || *winners > MAX_LOTTERY_WINNERS {
+                return Err(NFTError::InvalidAmount);
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn write_distribution_mode(env: &Env, round: u32, mode: &DistributionMode) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RoundMode(round), mode);
+    emit_distribution_mode_set(env, round, mode);
+}
+
+/// A player in the running for a tiered or lottery opening.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Candidate {
+    pub player: Address,
+    /// Power-seconds over the period the opening closes.
+    pub weight: i128,
+    /// Lottery clock; the lowest clocks win. Unused by tiers.
+    pub key: u128,
+}
+
+/// Payout of a tiered or lottery round, held back while its opening walks the
+/// decks, and the best candidates seen so far, best first.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingAward {
+    pub payout: PotAmounts,
+    pub candidates: Vec<Candidate>,
+}
+
+pub fn read_pending_award(env: &Env, round: u32) -> Option<PendingAward> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::RoundAward(round))
+}
+
+pub fn write_pending_award(env: &Env, round: u32, pending: &PendingAward) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RoundAward(round), pending);
+}
+
+/// Holds `payout` in the pot for the winners of `round`, who are picked as its
+/// opening walks the decks.
+pub fn hold(env: &Env, round: u32, payout: &PotAmounts) {
+    write_pending_award(
+        env,
+        round,
+        &PendingAward {
+            payout: payout.clone(),
+            candidates: Vec::new(env),
+        },
+    );
+}
+
+/// Exponential clock of a lottery ticket of `weight`: -log2(u) / weight for a
+/// uniform u, with -log2(u) in 32 fractional bits, scaled by 2^64. Keeping the
+/// lowest clocks draws without replacement in proportion to weight
+/// (Efraimidis–Spirakis), so a lottery never needs the total weight.
+fn lottery_key(env: &Env, weight: i128) -> u128 {
+    let u = env.prng().gen_range::<u64>(1..=u64::MAX);
+    let shift = u.leading_zeros();
+    // Mantissa of u in [1, 2) with 63 fractional bits; squaring it yields the
+    // fractional bits of its log2 one at a time
+    let mut mantissa = u128::from(u << shift);
+    let mut fraction: u128 = 0;
+    for bit in (0..32).rev() {
+        mantissa = (mantissa * mantissa) >> 63;
+        if mantissa >> 64 != 0 {
+            mantissa >>= 1;
+            fraction |= 1 << bit;
+        }
+    }
+    let neg_log2 = (u128::from(shift + 1) << 32) - fraction;
+    (neg_log2 << 64) / weight.unsigned_abs()
+}
+
+/// Considers `player`, of positive `weight`, for the paid places of a tiered or
+/// lottery `mode`. Only the paid places are kept, so the candidates never
+/// outnumber MAX_TIERS or MAX_LOTTERY_WINNERS; tier ties go to the earlier deck.
+pub fn consider(env: &Env, mode: &DistributionMode, pending: &mut PendingAward, player: Address, weight: i128) {
+    let (places, lottery) = match mode {
+        DistributionMode::Proportional => return,
+        DistributionMode::Tiered(tiers) => (tiers.len(), false),
+        DistributionMode::Lottery(count) => (*count, true),
+    };
+    let key = if lottery { lottery_key(env, weight) } else { 0 };
+    let candidates = &mut pending.candidates;
+    let position = candidates
+        .iter()
+        .position(|other| if lottery { key < other.key } else { weight > other.weight })
+        .unwrap_or(candidates.len() as usize) as u32;
+    if position < places {
+        candidates.insert(position, Candidate { player, weight, key });
+        if candidates.len() > places {
+            candidates.pop_back();
+        }
+    }
+}
+
+/// Pays the payout held for `round` to the candidates its opening picked.
+/// Winners are credited claimable balances and their place is recorded; unpaid
+/// shares and dust stay in the pot. Returns the winners and what they were paid,
+/// or `None` if nothing was held for `round`.
+pub fn award(env: &Env, round: u32, mode: &DistributionMode) -> Option<(Vec<PotWinner>, PotAmounts)> {
+    let pending = read_pending_award(env, round)?;
+    env.storage()
+        .persistent()
+        .remove(&DataKey2::RoundAward(round));
+
+    let payout = &pending.payout;
+    let count = pending.candidates.len();
+    let mut winners = Vec::new(env);
+    let (mut terry_paid, mut power_paid, mut xtar_paid) = (0, 0, 0);
+    for (i, candidate) in pending.candidates.iter().enumerate() {
+        let winner = PotWinner {
+            player: candidate.player,
+            share_bps: match mode {
+                DistributionMode::Tiered(tiers) => tiers.get(i as u32).unwrap_or(0),
+                _ => 10000 / count,
+            },
+        };
+        let bps = winner.share_bps as i128;
+        let (terry_share, power_share, xtar_share) = (
+            payout.terry * bps / 10000,
+            (payout.power as i128 * bps / 10000) as u32,
+            payout.xtar * bps / 10000,
+        );
+        credit(
+            env,
+            &winner.player,
+            round,
+            &PotAmounts {
+                terry: terry_share,
+                power: power_share,
+                xtar: xtar_share,
+            },
+        );
+        terry_paid += terry_share;
+        power_paid += power_share;
+        xtar_paid += xtar_share;
+
+        let deck = read_deck(env.clone(), winner.player.clone());
+        write_player_reward(
+            env,
+            round,
+            &winner.player,
+            &PlayerReward {
+                share_percentage: winner.share_bps,
+                effective_power: read_effective_power(env, &winner.player),
+                round_number: round,
+                deck_bonus: deck.bonus,
+                deck_categories: deck.deck_categories,
+                rank: i as u32 + 1,
+            },
+        );
+        emit_pot_winner(env, round, &winner, terry_share, power_share, xtar_share);
+        winners.push_back(winner);
+    }
+    if terry_paid > 0 || power_paid > 0 || xtar_paid > 0 {
+        post_all(env, Account::Pot, Account::Claimable, terry_paid, power_paid, xtar_paid, Action::None);
+    }
+    tokens::award(env, round, &winners);
+    Some((
+        winners,
+        PotAmounts {
+            terry: terry_paid,
+            power: power_paid,
+            xtar: xtar_paid,
+        },
+    ))
+}
@@ -0,0 +1,172 @@
+use crate::actions::deck::read_decks;
+use crate::error::EconomyError;
+use crate::event::emit_pot_opening_progress;
+use crate::pot::distribution::{accrue, read_effective_power, read_player_power_time};
+use crate::pot::modes::{self, consider, read_distribution_mode, read_pending_award, write_pending_award};
+use crate::pot::management::{previous_round, read_pot_snapshot, write_player_reward, write_pot_snapshot};
+use crate::storage_types::{DataKey2, PlayerReward};
+use soroban_sdk::{contracttype, panic_with_error, Env};
+
+/// Most players a single `continue_open_pot` call may process.
+pub const MAX_OPENING_BATCH: u32 = 50;
+
+/// Progress of a pot opening run over several transactions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpeningProgress {
+    pub round: u32,
+    /// Participants settled so far.
+    pub processed: u32,
+    /// Participants frozen when the opening began.
+    pub total: u32,
+    pub finalized: bool,
+}
+
+pub fn read_opening(env: &Env, round: u32) -> Option<OpeningProgress> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::RoundOpening(round))
+}
+
+fn write_opening(env: &Env, progress: &OpeningProgress) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::RoundOpening(progress.round), progress);
+    emit_pot_opening_progress(env, progress);
+}
+
+/// Round whose batched opening has begun but not been finalized.
+pub fn read_opening_in_progress(env: &Env) -> Option<u32> {
+    env.storage().persistent().get(&DataKey2::OpeningInProgress)
+}
+
+/// Traps with `OpeningInProgress` while a batched opening awaits finalization.
+/// Entrypoints typed with either error enum share this guard.
+pub fn ensure_no_opening(env: &Env) {
+    if read_opening_in_progress(env).is_some() {
+        panic_with_error!(env, EconomyError::OpeningInProgress);
+    }
+}
+
+/// The unfinished opening of `round`; `NoOpeningInProgress` if there is none.
+fn in_progress(env: &Env, round: u32) -> Result<OpeningProgress, EconomyError> {
+    if read_opening_in_progress(env) != Some(round) {
+        return Err(EconomyError::NoOpeningInProgress);
+    }
+    read_opening(env, round).ok_or(EconomyError::NoOpeningInProgress)
+}
+
+/// Freezes the participant cursor for `round`, whose snapshot has just been taken.
+/// Decks are only ever appended, so the first `total` of them stay the same.
+pub fn begin(env: &Env, round: u32) -> OpeningProgress {
+    let progress = OpeningProgress {
+        round,
+        processed: 0,
+        total: read_decks(env.clone()).len(),
+        finalized: false,
+    };
+    write_opening(env, &progress);
+    env.storage()
+        .persistent()
+        .set(&DataKey2::OpeningInProgress, &round);
+    progress
+}
+
+/// Settles the next `batch_size` participants of `round`: records their share of
+/// a proportional round, or weighs them for the winners of a tiered or lottery one.
+pub fn advance(env: &Env, round: u32, batch_size: u32) -> Result<OpeningProgress, EconomyError> {
+    if batch_size == 0 || batch_size > MAX_OPENING_BATCH {
+        return Err(EconomyError::InvalidBatchSize);
+    }
+    let mut progress = in_progress(env, round)?;
+    let end = (progress.processed + batch_size).min(progress.total);
+    process(env, round, progress.processed, end);
+    progress.processed = end;
+    write_opening(env, &progress);
+    Ok(progress)
+}
+
+/// Marks `round` done once every participant has been processed, paying the
+/// winners of a tiered or lottery round, which unblocks claims;
+/// `OpeningIncomplete` before that.
+pub fn finalize(env: &Env, round: u32) -> Result<OpeningProgress, EconomyError> {
+    let mut progress = in_progress(env, round)?;
+    if progress.processed < progress.total {
+        return Err(EconomyError::OpeningIncomplete);
+    }
+    award(env, round);
+    progress.finalized = true;
+    write_opening(env, &progress);
+    env.storage()
+        .persistent()
+        .remove(&DataKey2::OpeningInProgress);
+    Ok(progress)
+}
+
+/// Walks every deck and pays the winners of `round` in one call, for openings
+/// that are not batched.
+pub fn run(env: &Env, round: u32) {
+    process(env, round, 0, read_decks(env.clone()).len());
+    award(env, round);
+}
+
+/// Settles decks `from..to` for the opening of `round`: their power-seconds over
+/// the period the round paid out either make their share of it or weigh them for
+/// its winners.
+fn process(env: &Env, round: u32, from: u32, to: u32) {
+    let total_power_time = read_pot_snapshot(env, round)
+        .map(|snapshot| snapshot.time_weighted_power)
+        .unwrap_or(0);
+    let period = previous_round(env, round);
+    let mode = read_distribution_mode(env, round);
+    // Tiered and lottery rounds hold their payout until finalization
+    let mut pending = read_pending_award(env, round);
+
+    let decks = read_decks(env.clone());
+    for i in from..to {
+        let deck = decks.get(i).unwrap();
+        let power_time = read_player_power_time(env, &deck.owner, period);
+        if power_time == 0 {
+            continue;
+        }
+        accrue(env, &deck.owner);
+        if let Some(pending) = pending.as_mut() {
+            consider(env, &mode, pending, deck.owner, power_time);
+            continue;
+        }
+        let share_percentage = if total_power_time > 0 {
+            (power_time * 10000 / total_power_time) as u32
+        } else {
+            0
+        };
+        let effective_power = read_effective_power(env, &deck.owner);
+        write_player_reward(
+            env,
+            round,
+            &deck.owner,
+            &PlayerReward {
+                share_percentage,
+                effective_power,
+                round_number: round,
+                deck_bonus: deck.bonus,
+                deck_categories: deck.deck_categories,
+                rank: 0,
+            },
+        );
+    }
+    if let Some(pending) = pending {
+        write_pending_award(env, round, &pending);
+    }
+}
+
+/// Pays the winners held for `round`, if any, and records them in its snapshot.
+fn award(env: &Env, round: u32) {
+    let Some((winners, paid)) = modes::award(env, round, &read_distribution_mode(env, round)) else {
+        return;
+    };
+    if let Some(mut snapshot) = read_pot_snapshot(env, round) {
+        snapshot.distributed = paid;
+        snapshot.winners = winners;
+        write_pot_snapshot(env, round, &snapshot);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::event::emit_jackpot_released;
+use crate::ledger::{post_all, read_account, Account};
+use crate::nft_info::Action;
+use crate::storage_types::{Config, PotAmounts};
+use soroban_sdk::Env;
+
+/// How an opening splits the pot.
+pub struct PotSplit {
+    /// Offered to players this round, jackpot included.
+    pub distributable: PotAmounts,
+    /// Kept in the pot for the next round.
+    pub rolled_over: PotAmounts,
+    /// Moved into the jackpot reserve.
+    pub reserved: PotAmounts,
+    /// Released from the jackpot reserve into this round.
+    pub jackpot: PotAmounts,
+}
+
+pub fn zero() -> PotAmounts {
+    PotAmounts {
+        terry: 0,
+        power: 0,
+        xtar: 0,
+    }
+}
+
+fn is_zero(amounts: &PotAmounts) -> bool {
+    amounts.terry == 0 && amounts.power == 0 && amounts.xtar == 0
+}
+
+fn portion(amounts: &PotAmounts, bps: u32) -> PotAmounts {
+    let bps = bps as i128;
+    PotAmounts {
+        terry: amounts.terry * bps / 10000,
+        power: (amounts.power as i128 * bps / 10000) as u32,
+        xtar: amounts.xtar * bps / 10000,
+    }
+}
+
+/// Sets `jackpot_reserve_bps` of `pot` aside into the jackpot reserve and keeps
+/// `pot_rollover_bps` of it for the next round. The whole reserve is released
+/// into the round when this `opening` is a multiple of `jackpot_interval`.
+pub fn split(env: &Env, config: &Config, round: u32, opening: u32, pot: &PotAmounts) -> PotSplit {
+    let reserved = portion(pot, config.jackpot_reserve_bps);
+    if !is_zero(&reserved) {
+        post_all(
+            env,
+            Account::Pot,
+            Account::Jackpot,
+            reserved.terry,
+            reserved.power,
+            reserved.xtar,
+            Action::None,
+        );
+    }
+    let rolled_over = portion(pot, config.pot_rollover_bps);
+
+    let mut jackpot = zero();
+    if config.jackpot_interval > 0 && opening.is_multiple_of(config.jackpot_interval) {
+        let reserve = read_account(env, Account::Jackpot);
+        jackpot = PotAmounts {
+            terry: reserve.terry,
+            power: reserve.power as u32,
+            xtar: reserve.xtar,
+        };
+        if !is_zero(&jackpot) {
+            post_all(
+                env,
+                Account::Jackpot,
+                Account::Pot,
+                jackpot.terry,
+                jackpot.power,
+                jackpot.xtar,
+                Action::None,
+            );
+            emit_jackpot_released(env, round, &jackpot);
+        }
+    }
+
+    PotSplit {
+        distributable: PotAmounts {
+            terry: pot.terry - reserved.terry - rolled_over.terry + jackpot.terry,
+            power: pot.power - reserved.power - rolled_over.power + jackpot.power,
+            xtar: pot.xtar - reserved.xtar - rolled_over.xtar + jackpot.xtar,
+        },
+        rolled_over,
+        reserved,
+        jackpot,
+    }
+}
@@ -1,117 +1,103 @@
-use crate::admin::read_config;
-use crate::event::*;
-use crate::pot::management::{
-    get_all_rounds, read_player_reward, read_pot_snapshot, write_pending_reward,
-    write_player_reward,
+use crate::pot::management::{POT_BUMP_AMOUNT, POT_LIFETIME_THRESHOLD};
+use crate::storage_types::{
+    ClaimableRound, DataKey, PendingReward, PotAmounts, RewardClaim, RewardStatus,
+    UserClaimableBalance,
 };
-use crate::storage_types::DataKey;
-use crate::storage_types::{PendingReward, PlayerReward, PotSnapshot, RewardClaim, RewardStatus};
-use crate::user_info::{read_user, write_user};
-use soroban_sdk::{token, Address, Env, Vec};
-
-const MIN_REWARD_AMOUNT: i128 = 1;
-const MIN_XLM_RESERVE: i128 = 500_0000; // 0.5 XLM in stroops
-
-// pub fn verify_trustline(e: &Env, player: &Address, token_id: &Address) -> bool {
-//     let token_client = token::Client::new(e, token_id);
-//     // Check if the player has a trustline by attempting to get their balance
-//     token_client.balance(player) >= 0
-// }
-
-// pub fn check_xlm_reserve(e: &Env, player: &Address) -> bool {
-//     let native_token = token::Client::new(e, &e.stellar_asset_address("native"));
-//     // Check if the player has enough XLM for the trustline reserve
-//     native_token.balance(player) >= MIN_XLM_RESERVE
-// }
-
-
-
-pub fn process_reward(e: &Env, player: &Address, reward: &PendingReward) -> RewardStatus {
-    let mut user = read_user(e, player.clone());
-    let _config = read_config(e);
-    let mut updated = false;
-    let final_status = RewardStatus::Claimed;
-
-    if reward.terry_amount > MIN_REWARD_AMOUNT {
-        user.terry += reward.terry_amount;
-        updated = true;
+use soroban_sdk::{Address, Env, Vec};
+
+/// Most claim records a single `claim_history` call may return.
+pub const MAX_CLAIM_HISTORY_PAGE: u32 = 50;
+
+fn pending_reward(round: u32, terry: i128, power: u32, xtar: i128, status: RewardStatus) -> PendingReward {
+    PendingReward {
+        round_number: round,
+        terry_amount: terry,
+        power_amount: power,
+        xtar_amount: xtar,
+        status,
     }
+}
 
-    if reward.power_amount > 0 {
-        user.power += reward.power_amount;
-        updated = true;
+/// `total` broken down by the rounds in `parts`, all with `status`. Whatever the
+/// rounds do not account for, i.e. balances credited before rewards were tracked
+/// by round, is listed under round 0.
+pub fn by_round(
+    env: &Env,
+    parts: &Vec<ClaimableRound>,
+    total: &PotAmounts,
+    status: RewardStatus,
+) -> Vec<PendingReward> {
+    let mut rewards = Vec::new(env);
+    let mut rest = total.clone();
+    for part in parts.iter() {
+        if part.terry == 0 && part.power == 0 && part.xtar == 0 {
+            continue;
+        }
+        rest.terry -= part.terry;
+        rest.power -= part.power;
+        rest.xtar -= part.xtar;
+        rewards.push_back(pending_reward(part.round, part.terry, part.power, part.xtar, status.clone()));
     }
-
-    // if reward.xtar_amount > MIN_REWARD_AMOUNT {
-    //     let xtar_token = token::Client::new(e, &config.xtar_token);
-    //     if verify_trustline(e, player, &config.xtar_token) {
-    //         xtar_token.transfer(&e.current_contract_address(), player, &reward.xtar_amount);
-    //         updated = true;
-    //     } else {
-    //         final_status = RewardStatus::AwaitingTrustLine;
-    //         // Emit RewardPending instead of trustLine_required
-    //         emit_reward_pending(e, player, reward);
-    //     }
-    // }
-
-    if updated {
-        write_user(e, player.clone(), user);
+    if rest.terry > 0 || rest.power > 0 || rest.xtar > 0 {
+        rewards.push_front(pending_reward(0, rest.terry, rest.power, rest.xtar, status));
     }
-
-    final_status
+    rewards
 }
 
-pub fn claim_all_pending_rewards(e: Env, player: Address) {
-    player.require_auth();
-
-    let all_rounds = get_all_rounds(&e);
-    let mut pending_rewards = Vec::new(&e);
-    let timestamp = e.ledger().timestamp();
+/// Unclaimed rewards of a player by round.
+pub fn pending_rewards(env: &Env, claimable: &UserClaimableBalance) -> Vec<PendingReward> {
+    by_round(
+        env,
+        &claimable.rounds,
+        &PotAmounts {
+            terry: claimable.terry,
+            power: claimable.power,
+            xtar: claimable.xtar,
+        },
+        RewardStatus::Pending,
+    )
+}
 
-    for round in all_rounds.iter() {
-        if let Some(player_reward) = read_player_reward(&e, round, &player) {
-            if let Some(snapshot) = read_pot_snapshot(&e, round) {
-                let share = player_reward.share_percentage as i128;
-                let reward = PendingReward {
-                    round_number: round,
-                    terry_amount: (snapshot.total_terry * share) / 10000,
-                    power_amount: ((snapshot.total_power as i128 * share) / 10000) as u32,
-                    xtar_amount: (snapshot.total_xtar * share) / 10000,
-                    status: RewardStatus::Pending,
-                };
+fn read_claim_count(env: &Env, player: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::RewardClaimCount(player.clone()))
+        .unwrap_or(0)
+}
 
-                // First, atomically mark the reward as being processed to prevent race conditions
-                let key = DataKey::PlayerShare(round, player.clone());
-                
-                // Check if reward still exists (prevents double-claim)
-                if !e.storage().persistent().has(&key) {
-                    continue; // Already claimed, skip
-                }
-                
-                // Remove reward BEFORE processing to prevent double-spending
-                e.storage().persistent().remove(&key);
-                
-                let status = process_reward(&e, &player, &reward);
-                if status == RewardStatus::Claimed {
-                    emit_reward_claimed(&e, &player, &reward);
-                } else {
-                    // If processing failed, write as pending (but not back to PlayerShare)
-                    let pending_reward = PendingReward { status, ..reward };
-                    write_pending_reward(&e, round, &player, &pending_reward);
-                    pending_rewards.push_back(pending_reward);
-                }
-            }
-        }
-    }
+/// Stores a claim of `rewards` by `player` and returns it.
+pub fn record_claim(env: &Env, player: &Address, rewards: Vec<PendingReward>) -> RewardClaim {
+    let id = read_claim_count(env, player);
+    let claim = RewardClaim {
+        id,
+        player: player.clone(),
+        rewards,
+        claimed_at: env.ledger().timestamp(),
+    };
+    let key = DataKey::RewardClaim(player.clone(), id);
+    env.storage().persistent().set(&key, &claim);
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, POT_LIFETIME_THRESHOLD, POT_BUMP_AMOUNT);
+    env.storage()
+        .persistent()
+        .set(&DataKey::RewardClaimCount(player.clone()), &(id + 1));
+    claim
+}
 
-    if !pending_rewards.is_empty() {
-        let claim = RewardClaim {
-            player: player.clone(),
-            rewards: pending_rewards,
-            claimed_at: timestamp,
-        };
-        e.storage()
+/// Up to `limit` (at most MAX_CLAIM_HISTORY_PAGE) of the player's claims from id
+/// `start` on, oldest first.
+pub fn read_claim_history(env: &Env, player: &Address, start: u32, limit: u32) -> Vec<RewardClaim> {
+    let end = read_claim_count(env, player).min(start.saturating_add(limit.min(MAX_CLAIM_HISTORY_PAGE)));
+    let mut claims = Vec::new(env);
+    for id in start..end {
+        if let Some(claim) = env
+            .storage()
             .persistent()
-            .set(&DataKey::RewardClaim(player, timestamp), &claim);
+            .get(&DataKey::RewardClaim(player.clone(), id))
+        {
+            claims.push_back(claim);
+        }
     }
+    claims
 }
@@ -0,0 +1,294 @@
+use crate::admin::{read_administrator, read_config};
+use crate::error::{EconomyError, NFTError};
+use crate::event::{emit_pot_token_claimed, emit_pot_token_deposited, emit_pot_token_registered};
+use crate::fees::read_action_fees;
+use crate::ledger::{post, Account, Asset};
+use crate::nft_info::Action;
+use crate::pot::modes::PotWinner;
+use crate::pot::opening::ensure_no_opening;
+use crate::pot::distribution::{self, earned, read_unsettled, spread, RewardPeriod};
+use crate::storage_types::{Config, DataKey, DataKey2};
+use soroban_sdk::{contracttype, panic_with_error, token, Address, Env, Vec};
+
+/// A registered token and its undistributed pot.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PotTokenBalance {
+    pub token: Address,
+    /// Deposited since the last opening, plus rounding dust.
+    pub accumulated: i128,
+}
+
+/// SEP-41 tokens accepted into the pot besides XTAR, in registration order.
+///
+/// Registered tokens are booked in the ledger and reconciled by `audit_token`.
+/// Their shares are claimed in full from a single balance, so they cannot roll
+/// over, feed the jackpot reserve, vest or expire: tokens are only accepted while
+/// those policies are off, and the policies cannot be turned on while tokens are
+/// registered (`TokenPolicyUnsupported`). Keepers are not paid in tokens.
+pub fn read_pot_tokens(env: &Env) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::SponsorTokens)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Whether `config` enables a pot policy registered tokens do not follow.
+fn has_token_unsupported_policy(config: &Config) -> bool {
+    config.pot_rollover_bps != 0
+        || config.jackpot_reserve_bps != 0
+        || config.vesting_period != 0
+        || config.claim_expiry_rounds != 0
+}
+
+/// Traps with `TokenPolicyUnsupported` if `config` is about to enable rollover,
+/// the jackpot reserve, vesting or expiry while tokens are registered.
+pub fn ensure_supported_policies(env: &Env, config: &Config) {
+    if has_token_unsupported_policy(config) && !read_pot_tokens(env).is_empty() {
+        panic_with_error!(env, EconomyError::TokenPolicyUnsupported);
+    }
+}
+
+/// Admin only. XTAR is tracked natively and cannot be registered again.
+pub fn register_pot_token(env: Env, token: Address) -> Result<(), EconomyError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    let config = read_config(&env);
+    if token == config.xtar_token || read_pot_tokens(&env).contains(&token) {
+        return Err(EconomyError::TokenAlreadyRegistered);
+    }
+    if has_token_unsupported_policy(&config) {
+        return Err(EconomyError::TokenPolicyUnsupported);
+    }
+    ensure_pot_token(&env, &token);
+    Ok(())
+}
+
+/// Adds `token` to the registered set if it is not there yet. No authorization
+/// check. Traps with `TokenPolicyUnsupported` under a policy tokens do not follow.
+pub fn ensure_pot_token(env: &Env, token: &Address) {
+    let mut tokens = read_pot_tokens(env);
+    if tokens.contains(token) {
+        return;
+    }
+    if has_token_unsupported_policy(&read_config(env)) {
+        panic_with_error!(env, EconomyError::TokenPolicyUnsupported);
+    }
+    tokens.push_back(token.clone());
+    env.storage()
+        .persistent()
+        .set(&DataKey2::SponsorTokens, &tokens);
+    emit_pot_token_registered(env, token);
+}
+
+pub fn read_pot_token_balance(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TokenPot(token.clone()))
+        .unwrap_or(0)
+}
+
+fn write_pot_token_balance(env: &Env, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TokenPot(token.clone()), &amount);
+}
+
+pub fn read_pot_token_balances(env: &Env) -> Vec<PotTokenBalance> {
+    let mut balances = Vec::new(env);
+    for token in read_pot_tokens(env).iter() {
+        balances.push_back(PotTokenBalance {
+            accumulated: read_pot_token_balance(env, &token),
+            token,
+        });
+    }
+    balances
+}
+
+/// Total of `token` distributed at the opening of `round`.
+pub fn read_pot_token_snapshot(env: &Env, round: u32, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey::PotSnapshotAsset(round, token.clone()))
+        .unwrap_or(0)
+}
+
+fn read_settled_claimable(env: &Env, player: &Address, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TokenClaimable(player.clone(), token.clone()))
+        .unwrap_or(0)
+}
+
+fn read_token_index(env: &Env, token: &Address) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TokenIndex(token.clone()))
+        .unwrap_or(0)
+}
+
+/// Rate and index of `token` for the period of round `period`; zero for periods
+/// closed before the token was registered.
+fn read_token_period(env: &Env, token: &Address, period: u32) -> (i128, i128) {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TokenPeriod(token.clone(), period))
+        .unwrap_or((0, 0))
+}
+
+/// What `effective_power`, holding `power_time` in the period of round `period`
+/// and unchanged since, has earned in `token`.
+fn token_earned(env: &Env, token: &Address, period: u32, power_time: i128, effective_power: u32) -> i128 {
+    let (rate, index) = read_token_period(env, token, period);
+    earned(rate, power_time, effective_power, index, read_token_index(env, token))
+}
+
+/// Owed to a player in `token`, including rewards not yet settled.
+pub fn read_pot_token_claimable(env: &Env, player: &Address, token: &Address) -> i128 {
+    let pending = read_unsettled(env, player)
+        .map(|(period, power_time, power)| token_earned(env, token, period, power_time, power))
+        .unwrap_or(0);
+    read_settled_claimable(env, player, token) + pending
+}
+
+fn write_pot_token_claimable(env: &Env, player: &Address, token: &Address, amount: i128) {
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TokenClaimable(player.clone(), token.clone()), &amount);
+}
+
+/// Adds a registered token to the pot. Sponsor deposits carry no protocol fee.
+pub fn deposit(env: Env, from: Address, token: Address, amount: i128) -> Result<(), EconomyError> {
+    from.require_auth();
+
+    if amount <= 0 {
+        panic_with_error!(&env, NFTError::InvalidAmount);
+    }
+    if !read_pot_tokens(&env).contains(&token) {
+        return Err(EconomyError::TokenNotRegistered);
+    }
+    token::Client::new(&env, &token).transfer(&from, env.current_contract_address(), &amount);
+    credit(&env, &token, amount, Action::None);
+    emit_pot_token_deposited(&env, &from, &token, amount);
+    Ok(())
+}
+
+/// Adds tokens the contract already received to the pot of a registered token.
+pub fn credit(env: &Env, token: &Address, amount: i128, action: Action) {
+    post(env, Asset::Token(token.clone()), Account::External, Account::Pot, amount, action);
+    write_pot_token_balance(env, token, read_pot_token_balance(env, token) + amount);
+}
+
+/// Adds a payment the contract already received in `token` to its pot, less the
+/// protocol fee of `action`, which waits in the Dogstar fee account like the
+/// fees on TERRY and XTAR inflows. Returns the fee.
+pub fn accumulate(env: &Env, token: &Address, amount: i128, action: Action) -> i128 {
+    let fee = amount * read_action_fees(env, &action).protocol_fee_bps as i128 / 10000;
+    post(
+        env,
+        Asset::Token(token.clone()),
+        Account::External,
+        Account::DogstarFees,
+        fee,
+        action.clone(),
+    );
+    credit(env, token, amount - fee, action);
+    fee
+}
+
+/// Snapshots every registered token for `round` and pays it out over the
+/// power-seconds of `closed`, the period of round `period`, by raising the token's
+/// index. Rounding dust stays in the pot for the next round.
+/// Without `proportional` the pot is left for `award` and only the period is recorded.
+pub fn distribute(env: &Env, round: u32, period: u32, closed: &RewardPeriod, proportional: bool) {
+    let duration = (closed.end - closed.start) as i128;
+    for token in read_pot_tokens(env).iter() {
+        let total = read_pot_token_balance(env, &token);
+        env.storage()
+            .persistent()
+            .set(&DataKey::PotSnapshotAsset(round, token.clone()), &total);
+
+        let (rate, assigned) = if proportional {
+            spread(total, closed.power_time)
+        } else {
+            (0, 0)
+        };
+        post(
+            env,
+            Asset::Token(token.clone()),
+            Account::Pot,
+            Account::Claimable,
+            assigned,
+            Action::None,
+        );
+        let index = read_token_index(env, &token) + rate * duration;
+        env.storage()
+            .persistent()
+            .set(&DataKey2::TokenIndex(token.clone()), &index);
+        env.storage()
+            .persistent()
+            .set(&DataKey2::TokenPeriod(token.clone(), period), &(rate, index));
+        write_pot_token_balance(env, &token, total - assigned);
+    }
+}
+
+/// Pays each winner its share of every token pot as snapshotted at the opening
+/// of `round`; deposits made while the opening ran stay in the pot.
+pub fn award(env: &Env, round: u32, winners: &Vec<PotWinner>) {
+    if winners.is_empty() {
+        return;
+    }
+    for token in read_pot_tokens(env).iter() {
+        let total = read_pot_token_snapshot(env, round, &token);
+        let mut paid = 0;
+        for winner in winners.iter() {
+            let share = total * winner.share_bps as i128 / 10000;
+            if share > 0 {
+                let settled = read_settled_claimable(env, &winner.player, &token);
+                write_pot_token_claimable(env, &winner.player, &token, settled + share);
+                paid += share;
+            }
+        }
+        post(env, Asset::Token(token.clone()), Account::Pot, Account::Claimable, paid, Action::None);
+        write_pot_token_balance(env, &token, read_pot_token_balance(env, &token) - paid);
+    }
+}
+
+/// Moves what a player earned in every token from the period of round `period`
+/// through the period of round `through` into its claimable balances.
+pub fn settle(env: &Env, player: &Address, period: u32, power_time: i128, effective_power: u32, through: u32) {
+    for token in read_pot_tokens(env).iter() {
+        let (rate, from) = read_token_period(env, &token, period);
+        let (_, to) = read_token_period(env, &token, through);
+        let owed = earned(rate, power_time, effective_power, from, to);
+        if owed > 0 {
+            let settled = read_settled_claimable(env, player, &token);
+            write_pot_token_claimable(env, player, &token, settled + owed);
+        }
+    }
+}
+
+/// Pays a player everything owed in `token`.
+pub fn claim(env: Env, player: Address, token: Address) -> Result<i128, NFTError> {
+    player.require_auth();
+
+    ensure_no_opening(&env);
+    distribution::accrue(&env, &player);
+    let amount = read_settled_claimable(&env, &player, &token);
+    if amount == 0 {
+        return Err(NFTError::NoRewardsAvailable);
+    }
+    write_pot_token_claimable(&env, &player, &token, 0);
+    token::Client::new(&env, &token).transfer(&env.current_contract_address(), &player, &amount);
+    post(
+        &env,
+        Asset::Token(token.clone()),
+        Account::Claimable,
+        Account::External,
+        amount,
+        Action::None,
+    );
+    emit_pot_token_claimed(&env, &player, &token, amount);
+    Ok(amount)
+}
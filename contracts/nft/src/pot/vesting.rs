@@ -0,0 +1,86 @@
+use crate::storage_types::{ClaimableRound, PotAmounts, UserClaimableBalance};
+use soroban_sdk::{contracttype, Env, Vec};
+
+/// A player's claimable pot rewards split by whether they have unlocked yet.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingBalance {
+    /// Claimable now without penalty.
+    pub vested: PotAmounts,
+    /// Still unlocking; claiming it now costs `early_claim_penalty_bps`.
+    pub locked: PotAmounts,
+}
+
+/// Part of a round's remaining rewards unlocked by `now`.
+pub fn unlocked(entry: &ClaimableRound, now: u64) -> PotAmounts {
+    if now >= entry.vesting_end {
+        return PotAmounts {
+            terry: entry.terry,
+            power: entry.power,
+            xtar: entry.xtar,
+        };
+    }
+    let elapsed = now.saturating_sub(entry.vesting_start) as i128;
+    let duration = (entry.vesting_end - entry.vesting_start) as i128;
+    PotAmounts {
+        terry: entry.terry * elapsed / duration,
+        power: (entry.power as i128 * elapsed / duration) as u32,
+        xtar: entry.xtar * elapsed / duration,
+    }
+}
+
+/// Splits `claimable` into vested and locked amounts. Amounts not recorded
+/// under a round count as vested.
+pub fn read_vesting(env: &Env, claimable: &UserClaimableBalance) -> VestingBalance {
+    let now = env.ledger().timestamp();
+    let mut locked = PotAmounts {
+        terry: 0,
+        power: 0,
+        xtar: 0,
+    };
+    for entry in claimable.rounds.iter() {
+        let vested = unlocked(&entry, now);
+        locked.terry += entry.terry - vested.terry;
+        locked.power += entry.power - vested.power;
+        locked.xtar += entry.xtar - vested.xtar;
+    }
+    VestingBalance {
+        vested: PotAmounts {
+            terry: claimable.terry - locked.terry,
+            power: claimable.power - locked.power,
+            xtar: claimable.xtar - locked.xtar,
+        },
+        locked,
+    }
+}
+
+/// Removes the vested amounts from `claimable` and returns them, with the part
+/// each round gave. Each round keeps unlocking what is left of it from now until
+/// its original end.
+pub fn take_vested(env: &Env, claimable: &mut UserClaimableBalance) -> (PotAmounts, Vec<ClaimableRound>) {
+    let now = env.ledger().timestamp();
+    let vested = read_vesting(env, claimable).vested;
+    let mut rounds = Vec::new(env);
+    let mut parts = Vec::new(env);
+    for mut entry in claimable.rounds.iter() {
+        let unlocked = unlocked(&entry, now);
+        parts.push_back(ClaimableRound {
+            terry: unlocked.terry,
+            power: unlocked.power,
+            xtar: unlocked.xtar,
+            ..entry.clone()
+        });
+        entry.terry -= unlocked.terry;
+        entry.power -= unlocked.power;
+        entry.xtar -= unlocked.xtar;
+        if entry.terry > 0 || entry.power > 0 || entry.xtar > 0 {
+            entry.vesting_start = now;
+            rounds.push_back(entry);
+        }
+    }
+    claimable.terry -= vested.terry;
+    claimable.power -= vested.power;
+    claimable.xtar -= vested.xtar;
+    claimable.rounds = rounds;
+    (vested, parts)
+}
@@ -0,0 +1,237 @@
+use crate::actions::fight::{read_oracle_price, Asset as OracleAsset};
+use crate::admin::{read_administrator, read_config};
+use crate::curve;
+use crate::fees::read_action_fees;
+use crate::governance::timelock::ensure_no_timelock;
+use crate::error::{EconomyError, NFTError};
+use crate::event::{emit_payment_token_registered, emit_usd_price_set};
+use crate::metadata::{has_metadata, read_metadata, CardMetadata};
+use crate::nft_info::{Action, Currency};
+use crate::storage_types::{Config, DataKey2};
+use crate::pot::tokens::ensure_pot_token;
+use crate::sale::presale_price;
+use soroban_sdk::{contracttype, panic_with_error, token, Address, Env, Symbol, Vec};
+
+/// USD template prices carry this many decimals (1 USD = 10^7).
+pub const USD_DECIMALS: u32 = 7;
+
+/// A token accepted for USD-priced mints and the oracle asset that prices it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PaymentToken {
+    pub token: Address,
+    pub oracle_asset: OracleAsset,
+}
+
+/// What a mint costs and where the payment goes, in units of `currency`.
+#[contracttype]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MintQuote {
+    pub currency: Currency,
+    /// Total charged to the player.
+    pub price: i128,
+    /// XTAR burned.
+    pub burn: i128,
+    /// TERRY credited to the admin withdrawable balance.
+    pub admin: i128,
+    /// Added to the pot, after the protocol fee.
+    pub pot: i128,
+    /// Protocol fee taken from the pot share.
+    pub fee: i128,
+}
+
+pub fn read_usd_price(env: &Env, token_id: u32) -> Option<i128> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::UsdPrice(token_id))
+}
+
+/// Sets or clears (`None`) the USD price of a card template. Admin only, and only
+/// while no timelock delay is configured; otherwise queue a
+/// `ConfigChange::UsdPrice` or `ConfigChange::ClearUsdPrice`.
+pub fn set_usd_price(env: Env, token_id: u32, price_usd: Option<i128>) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    ensure_no_timelock(&read_config(&env))?;
+    validate_usd_price(&env, token_id, price_usd)?;
+    write_usd_price(&env, token_id, price_usd);
+    Ok(())
+}
+
+pub fn validate_usd_price(env: &Env, token_id: u32, price_usd: Option<i128>) -> Result<(), NFTError> {
+    if !has_metadata(env, token_id) {
+        return Err(NFTError::MetadataNotFound);
+    }
+    match price_usd {
+        Some(price) if price <= 0 => Err(NFTError::InvalidAmount),
+        _ => Ok(()),
+    }
+}
+
+pub fn write_usd_price(env: &Env, token_id: u32, price_usd: Option<i128>) {
+    let key = DataKey2::UsdPrice(token_id);
+    match price_usd {
+        Some(price) => env.storage().persistent().set(&key, &price),
+        None => env.storage().persistent().remove(&key),
+    }
+    emit_usd_price_set(env, token_id, price_usd);
+}
+
+pub fn read_payment_tokens(env: &Env) -> Vec<PaymentToken> {
+    let mut tokens = Vec::new(env);
+    let addresses: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey2::PaymentTokens)
+        .unwrap_or(Vec::new(env));
+    for token in addresses.iter() {
+        if let Some(oracle_asset) = read_payment_asset(env, &token) {
+            tokens.push_back(PaymentToken { token, oracle_asset });
+        }
+    }
+    tokens
+}
+
+fn read_payment_asset(env: &Env, token: &Address) -> Option<OracleAsset> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::PaymentAsset(token.clone()))
+}
+
+/// Accepts `token` for USD-priced mints, priced by `oracle_asset`. Admin only.
+/// Re-registering a token updates its oracle asset. Payments in tokens other
+/// than XTAR go to that token's pot, so the token is registered there as well.
+pub fn register_payment_token(env: Env, token: Address, oracle_asset: OracleAsset) {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    let mut addresses: Vec<Address> = env
+        .storage()
+        .persistent()
+        .get(&DataKey2::PaymentTokens)
+        .unwrap_or(Vec::new(&env));
+    if !addresses.contains(&token) {
+        addresses.push_back(token.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey2::PaymentTokens, &addresses);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey2::PaymentAsset(token.clone()), &oracle_asset);
+
+    if token != read_config(&env).xtar_token {
+        ensure_pot_token(&env, &token);
+    }
+    emit_payment_token_registered(&env, &token, &oracle_asset);
+}
+
+/// Whether a payment in `currency` is handled as XTAR (burned in part, pot in XTAR).
+pub fn pays_xtar(config: &Config, currency: &Currency) -> bool {
+    match currency {
+        Currency::Terry => false,
+        Currency::Xtar => true,
+        Currency::Token(token) => *token == config.xtar_token,
+    }
+}
+
+/// Amount of `currency` a mint of template `token_id` costs before its price
+/// curve. TERRY and XTAR use the template's fixed prices; registered tokens convert
+/// the USD price at the oracle price, rounded up. A running presale may override either.
+/// An unregistered token or a template without a USD price traps with its
+/// `EconomyError`.
+fn base_price(env: &Env, token_id: u32, metadata: &CardMetadata, currency: &Currency) -> Result<i128, NFTError> {
+    match currency {
+        Currency::Terry => Ok(presale_price(env, token_id, |sale| sale.presale_price_terry)
+            .unwrap_or(metadata.price_terry)),
+        Currency::Xtar => Ok(presale_price(env, token_id, |sale| sale.presale_price_xtar)
+            .unwrap_or(metadata.price_xtar)),
+        Currency::Token(token) => {
+            let oracle_asset = read_payment_asset(env, token)
+                .unwrap_or_else(|| panic_with_error!(env, EconomyError::UnknownPaymentToken));
+            let price_usd = presale_price(env, token_id, |sale| sale.presale_price_usd)
+                .or_else(|| read_usd_price(env, token_id))
+                .unwrap_or_else(|| panic_with_error!(env, EconomyError::UsdPriceNotSet));
+
+            let oracle = read_config(env).oracle_contract_id;
+            let oracle_price = read_oracle_price(env, &oracle, &oracle_asset)?;
+            let oracle_decimals: u32 =
+                env.invoke_contract(&oracle, &Symbol::new(env, "decimals"), Vec::new(env));
+            let token_decimals = token::Client::new(env, token).decimals();
+
+            // amount = price_usd / 10^USD_DECIMALS * 10^token_decimals / (oracle_price / 10^oracle_decimals)
+            let numerator = 10i128
+                .checked_pow(token_decimals + oracle_decimals)
+                .and_then(|scale| price_usd.checked_mul(scale))
+                .ok_or(NFTError::Overflow)?;
+            let denominator = oracle_price
+                .checked_mul(10i128.pow(USD_DECIMALS))
+                .ok_or(NFTError::Overflow)?;
+            Ok((numerator + denominator - 1) / denominator)
+        }
+    }
+}
+
+/// Price of the next mint and of the one after it, along the template's curve.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplatePrice {
+    pub supply: u32,
+    pub current: i128,
+    pub next: i128,
+}
+
+pub fn template_price(env: Env, token_id: u32, currency: Currency) -> Result<TemplatePrice, NFTError> {
+    if !has_metadata(&env, token_id) {
+        return Err(NFTError::MetadataNotFound);
+    }
+    let base = base_price(&env, token_id, &read_metadata(&env, token_id), &currency)?;
+    let supply = curve::read_supply(&env, token_id);
+    Ok(TemplatePrice {
+        supply,
+        current: curve::apply(&env, token_id, base, supply),
+        next: curve::apply(&env, token_id, base, supply + 1),
+    })
+}
+
+/// Prices a mint and splits the payment the way `mint` will. TERRY is split
+/// between the admin and the pot, XTAR between burning and the pot; the pot share
+/// then pays the Mint protocol fee. Other tokens go whole to their own pot.
+pub fn quote(
+    env: &Env,
+    token_id: u32,
+    metadata: &CardMetadata,
+    currency: &Currency,
+) -> Result<MintQuote, NFTError> {
+    let base = base_price(env, token_id, metadata, currency)?;
+    let price = curve::apply(env, token_id, base, curve::read_supply(env, token_id));
+    let config = read_config(env);
+
+    let (burn, admin) = if *currency == Currency::Terry {
+        (0, config.withdrawable_percentage as i128 * price / 100)
+    } else if pays_xtar(&config, currency) {
+        (config.burnable_percentage as i128 * price / 100, 0)
+    } else {
+        // Other tokens cannot be burned; all of the payment goes to that token's pot
+        (0, 0)
+    };
+    let pot_share = price - burn - admin;
+    let fee = pot_share * read_action_fees(env, &Action::Mint).protocol_fee_bps as i128 / 10000;
+    Ok(MintQuote {
+        currency: currency.clone(),
+        price,
+        burn,
+        admin,
+        pot: pot_share - fee,
+        fee,
+    })
+}
+
+/// Quote for minting template `token_id` with `currency`.
+pub fn mint_quote(env: Env, token_id: u32, currency: Currency) -> Result<MintQuote, NFTError> {
+    if !has_metadata(&env, token_id) {
+        return Err(NFTError::MetadataNotFound);
+    }
+    quote(&env, token_id, &read_metadata(&env, token_id), &currency)
+}
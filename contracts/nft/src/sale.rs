@@ -0,0 +1,178 @@
+use crate::admin::{is_whitelisted, read_administrator, read_config};
+use crate::error::{EconomyError, NFTError};
+use crate::event::emit_sale_configured;
+use crate::governance::timelock::ensure_no_timelock;
+use crate::metadata::has_metadata;
+use crate::storage_types::DataKey2;
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+/// Sale schedule of one card template. Templates without one can be minted at
+/// any time by anyone, at the template prices.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SaleConfig {
+    /// Presale opens to whitelisted wallets.
+    pub presale_start: u64,
+    /// Sale opens to everyone. Equal to `presale_start` for no presale.
+    pub public_start: u64,
+    /// Sale closes; 0 keeps it open indefinitely.
+    pub end: u64,
+    /// Mints allowed per wallet over the whole sale; 0 for no limit.
+    pub wallet_limit: u32,
+    /// Presale prices; 0 keeps the template (or USD) price.
+    pub presale_price_terry: i128,
+    pub presale_price_xtar: i128,
+    pub presale_price_usd: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SalePhase {
+    /// No sale configured; minting is unrestricted.
+    Open,
+    NotStarted,
+    Presale,
+    Public,
+    Ended,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TemplatePhase {
+    pub token_id: u32,
+    pub phase: SalePhase,
+}
+
+pub fn read_sale(env: &Env, token_id: u32) -> Option<SaleConfig> {
+    env.storage().persistent().get(&DataKey2::Sale(token_id))
+}
+
+fn read_sale_templates(env: &Env) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::SaleTemplates)
+        .unwrap_or(Vec::new(env))
+}
+
+/// Sets or removes (`None`) the sale schedule of a template. Admin only, and only
+/// while no timelock delay is configured; otherwise queue a
+/// `ConfigChange::Sale` or `ConfigChange::ClearSale`.
+pub fn set_sale(env: Env, token_id: u32, sale: Option<SaleConfig>) -> Result<(), NFTError> {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+
+    ensure_no_timelock(&read_config(&env))?;
+    validate_sale(&env, token_id, &sale)?;
+    write_sale(&env, token_id, &sale);
+    Ok(())
+}
+
+pub fn validate_sale(env: &Env, token_id: u32, sale: &Option<SaleConfig>) -> Result<(), NFTError> {
+    if !has_metadata(env, token_id) {
+        return Err(NFTError::MetadataNotFound);
+    }
+    let Some(config) = sale else {
+        return Ok(());
+    };
+    if config.public_start < config.presale_start
+        || (config.end != 0 && config.end <= config.public_start)
+    {
+        return Err(NFTError::InvalidHorizon);
+    }
+    if config.presale_price_terry < 0 || config.presale_price_xtar < 0 || config.presale_price_usd < 0 {
+        return Err(NFTError::NegativeAmount);
+    }
+    Ok(())
+}
+
+pub fn write_sale(env: &Env, token_id: u32, sale: &Option<SaleConfig>) {
+    let mut templates = read_sale_templates(env);
+    let position = templates.first_index_of(token_id);
+    match sale {
+        Some(config) => {
+            env.storage().persistent().set(&DataKey2::Sale(token_id), config);
+            if position.is_none() {
+                templates.push_back(token_id);
+            }
+        }
+        None => {
+            env.storage().persistent().remove(&DataKey2::Sale(token_id));
+            if let Some(position) = position {
+                templates.remove(position);
+            }
+        }
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey2::SaleTemplates, &templates);
+    emit_sale_configured(env, token_id, sale);
+}
+
+pub fn phase(env: &Env, token_id: u32) -> SalePhase {
+    let Some(sale) = read_sale(env, token_id) else {
+        return SalePhase::Open;
+    };
+    let now = env.ledger().timestamp();
+    if sale.end != 0 && now >= sale.end {
+        SalePhase::Ended
+    } else if now >= sale.public_start {
+        SalePhase::Public
+    } else if now >= sale.presale_start {
+        SalePhase::Presale
+    } else {
+        SalePhase::NotStarted
+    }
+}
+
+/// Current phase of every template with a sale schedule.
+pub fn read_phases(env: &Env) -> Vec<TemplatePhase> {
+    let mut phases = Vec::new(env);
+    for token_id in read_sale_templates(env).iter() {
+        phases.push_back(TemplatePhase {
+            token_id,
+            phase: phase(env, token_id),
+        });
+    }
+    phases
+}
+
+/// Presale price overriding the template price, if the presale is running and sets one.
+pub fn presale_price(env: &Env, token_id: u32, pick: fn(&SaleConfig) -> i128) -> Option<i128> {
+    if phase(env, token_id) != SalePhase::Presale {
+        return None;
+    }
+    read_sale(env, token_id)
+        .map(|sale| pick(&sale))
+        .filter(|price| *price > 0)
+}
+
+pub fn read_minted(env: &Env, token_id: u32, wallet: &Address) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::SaleMinted(token_id, wallet.clone()))
+        .unwrap_or(0)
+}
+
+/// Checks that `wallet` may mint `token_id` now and counts the mint. Fails with
+/// `SaleClosed` outside the sale window, `NotWhitelisted` for wallets off the
+/// whitelist during the presale and `WalletLimitExceeded` past the wallet limit.
+pub fn record_mint(env: &Env, token_id: u32, wallet: &Address) -> Result<(), EconomyError> {
+    let Some(sale) = read_sale(env, token_id) else {
+        return Ok(());
+    };
+    match phase(env, token_id) {
+        SalePhase::Public => {}
+        SalePhase::Presale if is_whitelisted(env, wallet) => {}
+        SalePhase::Presale => return Err(EconomyError::NotWhitelisted),
+        _ => return Err(EconomyError::SaleClosed),
+    }
+
+    let minted = read_minted(env, token_id, wallet);
+    if sale.wallet_limit != 0 && minted >= sale.wallet_limit {
+        return Err(EconomyError::WalletLimitExceeded);
+    }
+    env.storage()
+        .persistent()
+        .set(&DataKey2::SaleMinted(token_id, wallet.clone()), &(minted + 1));
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::nft_info::Category;
+use crate::ledger::{Account, Asset};
+use crate::nft_info::{Action, Category};
+use crate::pot::modes::{DistributionMode, PotWinner};
 use soroban_sdk::{contracttype, Address, String, Vec};
 
 pub(crate) const DAY_IN_LEDGERS: u32 = 17280;
@@ -49,6 +51,15 @@ pub struct PlayerReward {
     pub round_number: u32,
     pub deck_bonus: u32, // Bonus from the deck diversity
     pub deck_categories: u32,
+    pub rank: u32, // Place among the winners of a tiered or lottery round; 0 otherwise
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PotAmounts {
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
 }
 
 #[contracttype]
@@ -60,11 +71,26 @@ pub struct PotSnapshot {
     pub total_xtar: i128,
     pub timestamp: u64,
     pub total_participants: u32,
+    /// Effective power of all complete decks at the opening.
     pub total_effective_power: u32,
+    /// Effective power × seconds held over the period the opening pays out.
+    pub time_weighted_power: i128,
+    /// Paid out to players, jackpot included.
+    pub distributed: PotAmounts,
+    /// Left in the pot for the next round, rounding dust included.
+    pub rolled_over: PotAmounts,
+    /// Added to the jackpot reserve.
+    pub reserved: PotAmounts,
+    /// Jackpot reserve released into this round.
+    pub jackpot: PotAmounts,
+    pub mode: DistributionMode,
+    /// Players paid by a tiered or lottery opening, first place first.
+    pub winners: Vec<PotWinner>,
 }
 
+/// Pot rewards of one round and where they stand.
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PendingReward {
     pub round_number: u32,
     pub terry_amount: i128,
@@ -76,15 +102,20 @@ pub struct PendingReward {
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub enum RewardStatus {
+    /// Credited and not claimed yet.
     Pending,
+    /// Paid except for XTAR, which the player could not receive; it stays claimable.
     AwaitingTrustLine,
     Claimed,
+    /// Expired unclaimed and swept back into the pot.
     Failed,
 }
 
+/// One claim or sweep of a player's pot rewards, round by round.
 #[contracttype]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RewardClaim {
+    pub id: u32,
     pub player: Address,
     pub rewards: Vec<PendingReward>,
     pub claimed_at: u64,
@@ -112,6 +143,41 @@ pub struct ContractVault {
     pub total_claimable_xtar: i128,
 }
 
+/// Claim record as stored before rewards were tracked by round.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyClaimableBalance {
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+    pub last_claim_round: u32,
+    pub last_claim_timestamp: u64,
+}
+
+/// Opening snapshot as stored before rollover, jackpot and distribution modes.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyPotSnapshot {
+    pub round_number: u32,
+    pub total_terry: i128,
+    pub total_power: u32,
+    pub total_xtar: i128,
+    pub timestamp: u64,
+    pub total_participants: u32,
+    pub total_effective_power: u32,
+}
+
+/// Player share as stored before tiered and lottery rounds ranked winners.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyPlayerReward {
+    pub share_percentage: u32,
+    pub effective_power: u32,
+    pub round_number: u32,
+    pub deck_bonus: u32,
+    pub deck_categories: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct UserClaimableBalance {
@@ -120,6 +186,23 @@ pub struct UserClaimableBalance {
     pub xtar: i128,
     pub last_claim_round: u32,
     pub last_claim_timestamp: u64,
+    /// Unclaimed pot rewards by the round that paid them; empty for fee balances.
+    pub rounds: Vec<ClaimableRound>,
+}
+
+/// Pot rewards a player was credited in one round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimableRound {
+    pub round: u32,
+    pub terry: i128,
+    pub power: u32,
+    pub xtar: i128,
+    /// Round from which the rewards can be swept back into the pot; 0 if they never expire.
+    pub expires_round: u32,
+    /// The amounts unlock linearly from `vesting_start` to `vesting_end`.
+    pub vesting_start: u64,
+    pub vesting_end: u64,
 }
 
 #[contracttype]
@@ -133,6 +216,32 @@ pub struct Deck {
     pub deck_categories: u32, // Number of unique categories (1–4)
 }
 
+/// Layout version of the stored `Config`, bumped whenever a field is added.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Config as stored before the timelock and pot round settings were added.
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct LegacyConfig {
+    pub xtar_token: Address,
+    pub oracle_contract_id: Address,
+    pub withdrawable_percentage: u32,
+    pub burnable_percentage: u32,
+    pub haw_ai_percentage: u32,
+    pub terry_per_power: i128,
+    pub stake_periods: Vec<u32>,
+    pub stake_interest_percentages: Vec<u32>,
+    pub power_action_fee: u32,
+    pub burn_receive_percentage: u32,
+    pub terry_per_deck: i128,
+    pub terry_per_fight: i128,
+    pub terry_per_lending: i128,
+    pub terry_per_stake: i128,
+    pub apy_alpha: u32,
+    pub power_to_usdc_rate: i128,
+    pub dogstar_fee_percentage: u32,
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
@@ -153,6 +262,15 @@ pub struct Config {
     pub apy_alpha: u32,
     pub power_to_usdc_rate: i128, // e.g., 1000 for 0.10 USDC per POWER (1000/10000 = 0.10)
     pub dogstar_fee_percentage: u32, // Basis points (e.g., 500 = 5%)
+    pub param_change_delay: u64, // Seconds a queued parameter change waits before it can be executed
+    pub round_duration: u64, // Seconds a pot round runs before anyone can advance it; 0 disables advance_round
+    pub keeper_reward_bps: u32, // Basis points of the pot paid to the caller of advance_round
+    pub pot_rollover_bps: u32, // Basis points of the pot kept for the next round at each opening
+    pub jackpot_reserve_bps: u32, // Basis points of the pot added to the jackpot reserve at each opening
+    pub jackpot_interval: u32, // The reserve is paid out with every this many openings; 0 never pays it out
+    pub claim_expiry_rounds: u32, // Rounds after which unclaimed pot rewards can be swept back into the pot; 0 never expires them
+    pub vesting_period: u64, // Seconds over which each round's pot rewards unlock linearly; 0 pays them out unlocked
+    pub early_claim_penalty_bps: u32, // Basis points of locked rewards forfeited to the pot when claiming them early
 }
 
 #[contracttype]
@@ -204,8 +322,6 @@ pub enum DataKey {
     PotBalance,
     Balance,
     BalanceSC(Category),
-    DogstarBalance,
-    ContractVault,
     // Global state and whitelists
     State,
     Whitelist(Address),
@@ -231,16 +347,90 @@ pub enum DataKey {
     TokenIdCounter,
     Metadata(TokenId),
     // Pot-specific snapshots and reward tracking
-    /// Total snapshot per asset at pot opening for round
-    PotSnapshotAsset(u32, TokenId),
+    /// Total of a registered pot token distributed at the opening of a round
+    PotSnapshotAsset(u32, Address),
     PotSnapshotSC(u32, Category),
     OpeningSnapshot(u32),
     PlayerShare(u32, Address),
     PlayerPower(u32, Address),
-    PendingReward(u32, Address),
+    RewardClaimCount(Address),
     UserClaimableBalance(Address),
     DogstarClaimableBalance,
     CurrentRound,
     AllRounds,
-    RewardClaim(Address, u64),
+    RewardClaim(Address, u32),
+    // Timelocked parameter changes
+    QueuedChangeCounter,
+    QueuedChangeIds,
+    QueuedChange(u32),
+    // Governance
+    GovernanceSettings,
+    Proposer(Address),
+    ProposalCounter,
+    Proposal(u32),
+    Vote(u32, Address),
+    // Double-entry ledger
+    LedgerBalance(Account, Asset),
+    JournalCounter,
+    Journal(u32),
+}
+
+/// Storage keys added once `DataKey` reached the contracttype variant limit.
+/// Variant names must not clash with `DataKey`'s.
+#[derive(Clone, Debug)]
+#[contracttype]
+pub enum DataKey2 {
+    // Fee splits
+    FeeRecipients,
+    FeeClaimable(Address),
+    FeeTokenClaimable(Address, Address),
+    ActionFeeOverride(Action),
+    // Treasury
+    Treasurer,
+    TreasuryRecordCount,
+    TreasuryRecord(u32),
+    // Card sales and pricing
+    SaleTemplates,
+    Sale(u32),
+    SaleMinted(u32, Address),
+    UsdPrice(u32),
+    PaymentTokens,
+    PaymentAsset(Address),
+    PriceCurve(u32),
+    TemplateSupply(u32),
+    // Lazy pot distribution
+    Distribution,
+    PlayerCheckpoint(Address),
+    RewardPeriod(u32),
+    PowerChanges(Address),
+    RoundAfter(u32),
+    RoundBefore(u32),
+    // Batched pot openings and distribution modes
+    OpeningInProgress,
+    RoundOpening(u32),
+    RoundMode(u32),
+    RoundAward(u32),
+    // Sponsor tokens
+    SponsorTokens,
+    TokenPot(Address),
+    TokenClaimable(Address, Address),
+    TokenIndex(Address),
+    TokenPeriod(Address, u32),
+    // Auto-compounding
+    CompoundPreference(Address),
+    // Ledger journal index
+    JournalRange(u32),
+    // Voting weight checkpoints
+    VoteCheckpoints(Address),
+    VoteCheckpoint(Address, u32),
+    TotalVoteWeight,
+    // Timelocked changes queued by passed proposals
+    GovernanceChange(u32),
+    // Layout version of the stored config; missing means `LegacyConfig`
+    ConfigVersion,
+    // When the first keeper round started
+    RoundsStartedAt,
+    // Records replaced by the ledger, kept until `migrate` seeds it from them
+    ContractVault,
+    DogstarBalance,
 }
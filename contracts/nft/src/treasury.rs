@@ -0,0 +1,160 @@
+use crate::admin::{read_administrator, read_config};
+use crate::error::NFTError;
+use crate::event::{emit_haw_ai_swept, emit_treasurer_set, emit_treasury_withdrawn};
+use crate::ledger::{post, read_account_balance, Account, Asset};
+use crate::nft_info::Action;
+use crate::user_info::{mint_terry, read_user, write_user};
+use crate::storage_types::{DataKey2, BALANCE_BUMP_AMOUNT, BALANCE_LIFETIME_THRESHOLD};
+use soroban_sdk::{contracttype, token, vec, Address, Env, Vec};
+
+/// Most records a single `treasury_history` call may return.
+pub const MAX_TREASURY_HISTORY_PAGE: u32 = 50;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TreasuryOp {
+    /// Admin balance paid out to an address.
+    Withdraw,
+    /// Haw-AI balance moved into the pot.
+    Sweep,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TreasuryRecord {
+    pub id: u32,
+    pub op: TreasuryOp,
+    pub caller: Address,
+    pub to: Option<Address>,
+    pub asset: Asset,
+    pub amount: i128,
+    pub timestamp: u64,
+}
+
+pub fn read_treasurer(env: &Env) -> Option<Address> {
+    env.storage().persistent().get(&DataKey2::Treasurer)
+}
+
+/// Sets or clears the treasurer. Admin only.
+pub fn set_treasurer(env: Env, treasurer: Option<Address>) {
+    let admin = read_administrator(&env);
+    admin.require_auth();
+    match treasurer.clone() {
+        Some(address) => env.storage().persistent().set(&DataKey2::Treasurer, &address),
+        None => env.storage().persistent().remove(&DataKey2::Treasurer),
+    }
+    emit_treasurer_set(&env, treasurer);
+}
+
+fn read_record_count(env: &Env) -> u32 {
+    env.storage()
+        .persistent()
+        .get(&DataKey2::TreasuryRecordCount)
+        .unwrap_or(0)
+}
+
+/// Up to `limit` (at most MAX_TREASURY_HISTORY_PAGE) records from id `start` on,
+/// oldest first.
+pub fn read_treasury_history(env: &Env, start: u32, limit: u32) -> Vec<TreasuryRecord> {
+    let end = read_record_count(env).min(start.saturating_add(limit.min(MAX_TREASURY_HISTORY_PAGE)));
+    let mut history = vec![env];
+    for id in start..end {
+        if let Some(record) = env.storage().persistent().get(&DataKey2::TreasuryRecord(id)) {
+            history.push_back(record);
+        }
+    }
+    history
+}
+
+fn record(env: &Env, op: TreasuryOp, caller: &Address, to: Option<Address>, asset: Asset, amount: i128) {
+    let id = read_record_count(env);
+    let key = DataKey2::TreasuryRecord(id);
+    env.storage().persistent().set(
+        &key,
+        &TreasuryRecord {
+            id,
+            op,
+            caller: caller.clone(),
+            to,
+            asset,
+            amount,
+            timestamp: env.ledger().timestamp(),
+        },
+    );
+    env.storage()
+        .persistent()
+        .extend_ttl(&key, BALANCE_LIFETIME_THRESHOLD, BALANCE_BUMP_AMOUNT);
+    env.storage()
+        .persistent()
+        .set(&DataKey2::TreasuryRecordCount, &(id + 1));
+}
+
+fn require_treasury_auth(env: &Env, caller: &Address) -> Result<(), NFTError> {
+    caller.require_auth();
+    if *caller != read_administrator(env) && Some(caller.clone()) != read_treasurer(env) {
+        return Err(NFTError::NotAuthorized);
+    }
+    Ok(())
+}
+
+fn check_amount(env: &Env, account: Account, asset: Asset, amount: i128) -> Result<(), NFTError> {
+    if amount <= 0 {
+        return Err(NFTError::InvalidAmount);
+    }
+    if amount > read_account_balance(env, account, asset) {
+        return Err(NFTError::InsufficientTreasury);
+    }
+    Ok(())
+}
+
+/// Pays part of the admin balance out to `to`. TERRY and POWER are credited to
+/// the recipient's user balance; XTAR is transferred from the contract.
+pub fn withdraw_admin(
+    env: Env,
+    caller: Address,
+    to: Address,
+    asset: Asset,
+    amount: i128,
+) -> Result<(), NFTError> {
+    require_treasury_auth(&env, &caller)?;
+    check_amount(&env, Account::AdminWithdrawable, asset.clone(), amount)?;
+
+    match &asset {
+        Asset::Terry => mint_terry(&env, to.clone(), amount),
+        Asset::Power => {
+            let mut user = read_user(&env, to.clone());
+            user.power = u32::try_from(amount)
+                .ok()
+                .and_then(|power| user.power.checked_add(power))
+                .ok_or(NFTError::Overflow)?;
+            write_user(&env, to.clone(), user);
+        }
+        Asset::Xtar | Asset::Token(_) => {
+            let address = match &asset {
+                Asset::Token(address) => address.clone(),
+                _ => read_config(&env).xtar_token,
+            };
+            let token = token::Client::new(&env, &address);
+            if token.balance(&env.current_contract_address()) < amount {
+                return Err(NFTError::InsufficientTreasury);
+            }
+            token.transfer(&env.current_contract_address(), &to, &amount);
+        }
+    }
+
+    post(&env, asset.clone(), Account::AdminWithdrawable, Account::External, amount, Action::None);
+    record(&env, TreasuryOp::Withdraw, &caller, Some(to.clone()), asset.clone(), amount);
+    emit_treasury_withdrawn(&env, &caller, &to, asset, amount);
+    Ok(())
+}
+
+/// Moves part of the Haw-AI balance into the pot so it is paid out at the next opening.
+pub fn sweep_haw_ai(env: Env, caller: Address, asset: Asset, amount: i128) -> Result<(), NFTError> {
+    require_treasury_auth(&env, &caller)?;
+    check_amount(&env, Account::HawAi, asset.clone(), amount)?;
+
+    post(&env, asset.clone(), Account::HawAi, Account::Pot, amount, Action::None);
+    record(&env, TreasuryOp::Sweep, &caller, None, asset.clone(), amount);
+    emit_haw_ai_swept(&env, &caller, asset, amount);
+    Ok(())
+}
@@ -1,9 +1,10 @@
-use crate::error::MyLabError;
+use crate::error::NFTError;
+use crate::event::emit_level_up;
 use crate::nft_info::read_nft;
 use crate::storage_types::{DataKey, Level, TokenId, User};
 use soroban_sdk::{log, Address, Env, Vec};
 
-pub fn add_card_to_owner(env: &Env, token_id: TokenId, user: Address) -> Result<(), MyLabError> {
+pub fn add_card_to_owner(env: &Env, token_id: TokenId, user: Address) -> Result<(), NFTError> {
     log!(&env, "Add card to owner function");
     if let Some(card) = read_nft(&env, user.clone(), token_id.clone()) {
         log!(&env, "add_card_to_owner >> Found card {}", card.clone());
@@ -21,7 +22,7 @@ pub fn add_card_to_owner(env: &Env, token_id: TokenId, user: Address) -> Result<
             &env,
             "add_card_to_owner >> Card not found in add_card_to_owner"
         );
-        return Err(MyLabError::NotNFT);
+        return Err(NFTError::CardNotFound);
     }
 }
 
@@ -57,7 +58,9 @@ pub fn get_user_level(e: &Env, user: Address) -> u32 {
         .unwrap_or(0u32);
 
     for i in 1..=last_level_id {
-        let level: Level = e.storage().persistent().get(&DataKey::Level(i)).unwrap();
+        let Some(level) = e.storage().persistent().get::<_, Level>(&DataKey::Level(i)) else {
+            continue;
+        };
         if balance > level.minimum_terry && balance <= level.maximum_terry {
             return i;
         }
@@ -105,14 +108,26 @@ pub fn mint_terry(e: &Env, owner: Address, amount: i128) {
     let mut user = read_user(e, owner.clone());
     user.terry += amount;
     user.total_history_terry += amount;
-    user.level = get_user_level(e, owner.clone());
-    write_user(e, user.owner.clone(), user);
+    write_user(e, user.owner.clone(), user.clone());
+
+    // Level is derived from the stored lifetime TERRY, so recompute after the write
+    let new_level = get_user_level(e, owner.clone());
+    if new_level != user.level {
+        if new_level > user.level {
+            emit_level_up(e, &user.owner, user.level, new_level);
+        }
+        user.level = new_level;
+        write_user(e, user.owner.clone(), user);
+    }
 }
 
-pub fn burn_terry(e: &Env, owner: Address, amount: i128) {
+pub fn burn_terry(e: &Env, owner: Address, amount: i128) -> Result<(), NFTError> {
     let mut user = read_user(e, owner.clone());
-    assert!(user.terry >= amount, "Not enough terry to burn");
+    if user.terry < amount {
+        return Err(NFTError::InsufficientTerry);
+    }
     user.terry -= amount;
     write_user(e, user.owner.clone(), user);
     log!(&e, "burn_terry >> Burned terry {}", amount);
+    Ok(())
 }